@@ -0,0 +1,129 @@
+//! The pieces of a subtitle cue: its timing line and its text.
+
+use anyhow::{anyhow, bail, Context, Result};
+
+/// `text` without blank lines, which would end the cue early, and with
+/// `&`, `<` and `>` escaped so no line reads as markup or as a timing
+/// line (`-->`).
+pub(crate) fn cue_payload(text: &str) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// `line` with the character references `cue_payload` writes decoded.
+/// Anything else, such as a bare `&`, is kept as it is.
+fn unescape(line: &str) -> String {
+    line.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Split into blank-line separated blocks of trimmed lines.
+pub(crate) fn blocks(input: &str) -> Vec<Vec<&str>> {
+    let mut out = Vec::new();
+    let mut current = Vec::new();
+    for line in input.lines() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                out.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(line.trim_start_matches('\u{feff}'));
+        }
+    }
+    if !current.is_empty() {
+        out.push(current);
+    }
+    out
+}
+
+/// Join cue payload lines, dropping inline markup such as `<i>` or `<v Name>`
+/// and decoding escaped characters.
+pub(crate) fn cue_text<'a>(lines: impl Iterator<Item = &'a &'a str>) -> String {
+    let mut text = Vec::new();
+    for line in lines {
+        let mut plain = String::with_capacity(line.len());
+        let mut in_tag = false;
+        for ch in line.chars() {
+            match ch {
+                '<' => in_tag = true,
+                '>' if in_tag => in_tag = false,
+                _ if !in_tag => plain.push(ch),
+                _ => {}
+            }
+        }
+        text.push(unescape(plain.trim()));
+    }
+    text.join("\n").trim().to_string()
+}
+
+pub(crate) fn parse_timing(line: &str) -> Result<(u64, u64)> {
+    let (start, rest) = line
+        .split_once("-->")
+        .ok_or_else(|| anyhow!("Missing '-->' in timing line: {line}"))?;
+    // WebVTT allows cue settings after the end timestamp.
+    let end = rest.split_whitespace().next().unwrap_or_default();
+    let start_ms = parse_timestamp(start.trim())?;
+    let end_ms = parse_timestamp(end)?;
+    if end_ms < start_ms {
+        bail!("Cue ends before it starts: {line}");
+    }
+    Ok((start_ms, end_ms))
+}
+
+/// Accepts `HH:MM:SS,mmm`, `HH:MM:SS.mmm` and `MM:SS.mmm`. Shorter
+/// fractions are tenths or hundredths, so `01.5` is 1500 ms.
+fn parse_timestamp(s: &str) -> Result<u64> {
+    let (clock, fraction) = s
+        .rsplit_once([',', '.'])
+        .ok_or_else(|| anyhow!("Missing milliseconds in timestamp: {s}"))?;
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        bail!("Bad milliseconds in timestamp: {s}");
+    }
+    let millis: u64 = format!("{fraction:0<3}")
+        .parse()
+        .with_context(|| format!("Bad milliseconds in timestamp: {s}"))?;
+
+    let mut secs = 0u64;
+    let parts: Vec<&str> = clock.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        bail!("Bad timestamp: {s}");
+    }
+    for part in parts {
+        let v: u64 = part
+            .parse()
+            .with_context(|| format!("Bad timestamp component: {s}"))?;
+        secs = secs * 60 + v;
+    }
+    Ok(secs * 1000 + millis)
+}
+
+pub(crate) fn format_timestamp(ms: u64, sep: char) -> String {
+    let h = ms / 3_600_000;
+    let m = (ms / 60_000) % 60;
+    let s = (ms / 1000) % 60;
+    let millis = ms % 1000;
+    format!("{h:02}:{m:02}:{s:02}{sep}{millis:03}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_fractions() -> Result<()> {
+        assert_eq!(parse_timestamp("00:00:01.5")?, 1500);
+        assert_eq!(parse_timestamp("00:01,25")?, 1250);
+        assert!(parse_timestamp("00:00:01.5000").is_err());
+        assert!(parse_timestamp("00:00:01.").is_err());
+        assert_eq!(format_timestamp(3_723_004, ','), "01:02:03,004");
+        Ok(())
+    }
+}
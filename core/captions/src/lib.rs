@@ -1,12 +1,20 @@
 //! Caption rendering module
 //!
 //! Phase 1: Generates ffmpeg drawtext filter strings.
+//! Also converts captions to and from SRT / `WebVTT` subtitle files.
 
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_sign_loss)]
 #![allow(clippy::cast_precision_loss)]
 #![allow(clippy::many_single_char_names)]
 
+mod cue;
+mod subtitles;
+mod template;
+
+pub use subtitles::*;
+pub use template::CaptionTemplate;
+
 use anyhow::Result;
 use mandygif_protocol::Caption;
 
@@ -88,13 +96,31 @@ pub fn chain_filters_expr(captions: &[Caption]) -> Result<String> {
 
 /// Normalize CSS-like hex colors to ffmpeg syntax.
 /// #RGB/#RRGGBB/#RGBA/#RRGGBBAA -> 0xRRGGBB or 0xRRGGBB@A.A
-#[allow(clippy::cast_lossless)]
-fn ff_color(input: &str, default_alpha: f32) -> Result<String> {
+///
+/// # Errors
+/// Returns error if the color is not valid hex.
+pub fn ff_color(input: &str, default_alpha: f32) -> Result<String> {
     let s = input.trim();
     if s.starts_with("0x") || s.contains('@') {
         return Ok(s.to_string());
     }
 
+    let [r, g, b, a] = parse_hex_color(s, default_alpha)?;
+    if a == 255 {
+        Ok(format!("0x{r:02X}{g:02X}{b:02X}"))
+    } else {
+        let alpha = f32::from(a) / 255.0;
+        Ok(format!("0x{r:02X}{g:02X}{b:02X}@{alpha:.3}"))
+    }
+}
+
+/// Parse #RGB/#RRGGBB/#RGBA/#RRGGBBAA into RGBA bytes.
+///
+/// # Errors
+/// Returns error if the color is not valid hex.
+#[allow(clippy::cast_lossless)]
+pub fn parse_hex_color(input: &str, default_alpha: f32) -> Result<[u8; 4]> {
+    let s = input.trim();
     let hex = s.strip_prefix('#').unwrap_or(s);
     let (r, g, b, a) = match hex.len() {
         3 => {
@@ -125,11 +151,5 @@ fn ff_color(input: &str, default_alpha: f32) -> Result<String> {
         }
         _ => return Err(anyhow::anyhow!("Invalid hex color format: {s}")),
     };
-
-    if a == 255 {
-        Ok(format!("0x{r:02X}{g:02X}{b:02X}"))
-    } else {
-        let alpha = f32::from(a) / 255.0;
-        Ok(format!("0x{r:02X}{g:02X}{b:02X}@{alpha:.3}"))
-    }
+    Ok([r, g, b, a])
 }
@@ -0,0 +1,196 @@
+//! SRT / `WebVTT` import and export.
+//!
+//! Subtitle files only carry timing and text, so parsed cues are dressed
+//! with a `CaptionTemplate` (font, style, position) to become `Caption`s.
+
+use crate::cue::{blocks, cue_payload, cue_text, format_timestamp, parse_timing};
+use crate::template::CaptionTemplate;
+use anyhow::{bail, Result};
+use mandygif_protocol::Caption;
+use std::fmt::Write;
+
+/// Parse `SubRip` (`.srt`) content into captions.
+///
+/// # Errors
+/// Returns error if a cue has a malformed timing line.
+pub fn parse_srt(input: &str, template: &CaptionTemplate) -> Result<Vec<Caption>> {
+    let mut captions = Vec::new();
+    for block in blocks(input) {
+        let mut lines = block.iter();
+        let Some(mut first) = lines.next() else {
+            continue;
+        };
+        // The numeric cue index is optional in practice; skip it when present.
+        if !first.contains("-->") {
+            match lines.next() {
+                Some(next) => first = next,
+                None => continue,
+            }
+        }
+        let (start_ms, end_ms) = parse_timing(first)?;
+        let text = cue_text(lines);
+        if !text.is_empty() {
+            captions.push(template.caption(text, start_ms, end_ms));
+        }
+    }
+    Ok(captions)
+}
+
+/// Parse `WebVTT` (`.vtt`) content into captions.
+///
+/// Cue settings (`position:`, `align:` ...) and NOTE/STYLE/REGION blocks are
+/// ignored; placement always comes from the template.
+///
+/// # Errors
+/// Returns error if the `WEBVTT` header is missing or a timing line is malformed.
+pub fn parse_vtt(input: &str, template: &CaptionTemplate) -> Result<Vec<Caption>> {
+    let input = input.trim_start_matches('\u{feff}');
+    if !input.starts_with("WEBVTT") {
+        bail!("Missing WEBVTT header");
+    }
+
+    let mut captions = Vec::new();
+    for block in blocks(input).into_iter().skip(1) {
+        let Some(timing_idx) = block.iter().position(|l| l.contains("-->")) else {
+            continue; // NOTE, STYLE, REGION
+        };
+        let (start_ms, end_ms) = parse_timing(block[timing_idx])?;
+        let text = cue_text(block[timing_idx + 1..].iter());
+        if !text.is_empty() {
+            captions.push(template.caption(text, start_ms, end_ms));
+        }
+    }
+    Ok(captions)
+}
+
+/// Serialize captions as `SubRip`. Style and position are not representable.
+#[must_use]
+pub fn to_srt(captions: &[Caption]) -> String {
+    let mut out = String::new();
+    for (i, c) in captions.iter().enumerate() {
+        let _ = write!(
+            out,
+            "{}\n{} --> {}\n{}\n\n",
+            i + 1,
+            format_timestamp(c.start_ms, ','),
+            format_timestamp(c.end_ms, ','),
+            cue_payload(&c.text)
+        );
+    }
+    out
+}
+
+/// Serialize captions as `WebVTT`. Style and position are not representable.
+#[must_use]
+pub fn to_vtt(captions: &[Caption]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for c in captions {
+        let _ = write!(
+            out,
+            "{} --> {}\n{}\n\n",
+            format_timestamp(c.start_ms, '.'),
+            format_timestamp(c.end_ms, '.'),
+            cue_payload(&c.text)
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\r\n00:00:01,000 --> 00:00:02,500\r\nHello <i>world</i>\r\n\r\n\
+                       2\r\n00:00:03,000 --> 00:00:04,000\r\nSecond\r\nline\r\n";
+
+    #[test]
+    fn test_parse_srt() -> Result<()> {
+        let caps = parse_srt(SRT, &CaptionTemplate::default())?;
+        assert_eq!(caps.len(), 2);
+        assert_eq!(caps[0].text, "Hello world");
+        assert_eq!((caps[0].start_ms, caps[0].end_ms), (1000, 2500));
+        assert_eq!(caps[1].text, "Second\nline");
+        assert_eq!(caps[1].style, CaptionTemplate::default().style);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_vtt() -> Result<()> {
+        let vtt = "WEBVTT\n\nNOTE editor export\n\n\
+                   intro\n00:01.000 --> 00:02.000 align:start\n<v Mandy>Hi there\n";
+        let caps = parse_vtt(vtt, &CaptionTemplate::default())?;
+        assert_eq!(caps.len(), 1);
+        assert_eq!(caps[0].text, "Hi there");
+        assert_eq!((caps[0].start_ms, caps[0].end_ms), (1000, 2000));
+        Ok(())
+    }
+
+    #[test]
+    fn test_srt_roundtrip() -> Result<()> {
+        let template = CaptionTemplate::default();
+        let caps = parse_srt(SRT, &template)?;
+        assert_eq!(parse_srt(&to_srt(&caps), &template)?, caps);
+        assert_eq!(parse_vtt(&to_vtt(&caps), &template)?, caps);
+        Ok(())
+    }
+
+    #[test]
+    fn test_short_fractions() -> Result<()> {
+        let srt = "1\n00:00:01.5 --> 00:00:02.05\nShort\n";
+        let caps = parse_srt(srt, &CaptionTemplate::default())?;
+        assert_eq!((caps[0].start_ms, caps[0].end_ms), (1500, 2050));
+        assert_eq!(
+            parse_srt(&to_srt(&caps), &CaptionTemplate::default())?,
+            caps
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_blank_lines_stay_in_one_cue() -> Result<()> {
+        let template = CaptionTemplate::default();
+        let mut caps = parse_srt(SRT, &template)?;
+        caps[0].text = "First\n\n  \nSecond".into();
+
+        let srt = parse_srt(&to_srt(&caps), &template)?;
+        let vtt = parse_vtt(&to_vtt(&caps), &template)?;
+        for parsed in [srt, vtt] {
+            assert_eq!(parsed.len(), 2);
+            assert_eq!(parsed[0].text, "First\nSecond");
+            assert_eq!(parsed[1], caps[1]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrows_and_markup_are_escaped() -> Result<()> {
+        let template = CaptionTemplate::default();
+        let mut caps = parse_srt(SRT, &template)?;
+        caps[0].text = "a --> b\n<i> & &amp;".into();
+
+        let srt = to_srt(&caps);
+        let vtt = to_vtt(&caps);
+        assert!(srt.contains("a --&gt; b\n&lt;i&gt; &amp; &amp;amp;\n"));
+        for file in [&srt, &vtt] {
+            assert_eq!(file.matches("-->").count(), 2);
+        }
+        assert_eq!(parse_srt(&srt, &template)?, caps);
+        assert_eq!(parse_vtt(&vtt, &template)?, caps);
+
+        // Files from elsewhere may hold a bare ampersand
+        let raw = "1\n00:00:01,000 --> 00:00:02,000\nTom & Jerry &gt;\n";
+        assert_eq!(parse_srt(raw, &template)?[0].text, "Tom & Jerry >");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_bad_timing() {
+        let bad = "1\n00:00:02,000 --> 00:00:01,000\nBackwards\n";
+        assert!(parse_srt(bad, &CaptionTemplate::default()).is_err());
+        assert!(parse_vtt(
+            "1\n00:01.000 --> 00:02.000\nx\n",
+            &CaptionTemplate::default()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,47 @@
+//! Styling for cues read from subtitle files.
+
+use mandygif_protocol::{Caption, CaptionAnimation, CaptionRect, CaptionStyle};
+
+/// Styling applied to every cue imported from a subtitle file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptionTemplate {
+    pub font: String,
+    pub style: CaptionStyle,
+    pub rect: CaptionRect,
+    pub animation: CaptionAnimation,
+}
+
+impl Default for CaptionTemplate {
+    /// White text with a black outline, centered along the bottom edge.
+    fn default() -> Self {
+        Self {
+            font: "DejaVu Sans".to_string(),
+            style: CaptionStyle {
+                color: "#FFFFFF".to_string(),
+                stroke: "#000000".to_string(),
+                size: 32,
+            },
+            rect: CaptionRect {
+                x: 0.1,
+                y: 0.85,
+                w: 0.8,
+                h: 0.1,
+            },
+            animation: CaptionAnimation::None,
+        }
+    }
+}
+
+impl CaptionTemplate {
+    pub(crate) fn caption(&self, text: String, start_ms: u64, end_ms: u64) -> Caption {
+        Caption {
+            text,
+            font: self.font.clone(),
+            style: self.style.clone(),
+            rect: self.rect.clone(),
+            start_ms,
+            end_ms,
+            animation: self.animation.clone(),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Drops frames identical to the last one kept, for variable frame rate
+/// recordings.
+///
+/// Every frame is hashed whole with XXH3. The Linux recorder hands over
+/// what `videoconvert` makes for the encoder: planar I420, or Y444 for
+/// lossless recordings, so luma and chroma planes are both compared.
+/// Hashing only some rows would be cheaper, but would miss small changes
+/// such as a blinking caret between them.
+#[derive(Debug, Clone)]
+pub struct FrameDeduper {
+    max_gap_ns: u64,
+    last: Option<(u64, u64)>,
+    keep_next: bool,
+}
+
+impl FrameDeduper {
+    /// Never go more than `max_gap_ms` without keeping a frame.
+    #[must_use]
+    pub fn new(max_gap_ms: u32) -> Self {
+        Self {
+            max_gap_ns: u64::from(max_gap_ms) * 1_000_000,
+            last: None,
+            keep_next: false,
+        }
+    }
+
+    /// Whether the frame stamped `pts_ns` should be recorded.
+    pub fn keep(&mut self, pts_ns: u64, pixels: &[u8]) -> bool {
+        let hash = xxh3_64(pixels);
+
+        let keep = match self.last {
+            Some((last_ns, last_hash)) => {
+                hash != last_hash
+                    || self.keep_next
+                    || pts_ns.saturating_sub(last_ns) >= self.max_gap_ns
+            }
+            None => true,
+        };
+        if keep {
+            self.last = Some((pts_ns, hash));
+            self.keep_next = false;
+        }
+        keep
+    }
+
+    /// Keep the next frame even if unchanged, e.g. to close out a
+    /// recording at the right time.
+    pub fn keep_next(&mut self) {
+        self.keep_next = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_repeats_only_after_max_gap() {
+        let mut dedup = FrameDeduper::new(500);
+        let (a, b) = ([1u8; 64], [2u8; 64]);
+
+        let kept: Vec<u64> = (0..30)
+            .map(|i| i * 33)
+            .filter(|&t| dedup.keep(t * MS, if t < 400 { &a } else { &b }))
+            .collect();
+        // First frame, change at 429 ms, then one repeat per 500 ms
+        assert_eq!(kept, [0, 429, 957]);
+
+        assert!(!dedup.keep(990 * MS, &b));
+        dedup.keep_next();
+        assert!(dedup.keep(1023 * MS, &b));
+        assert!(!dedup.keep(1056 * MS, &b));
+    }
+}
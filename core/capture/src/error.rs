@@ -0,0 +1,37 @@
+use mandygif_protocol::ErrorKind;
+use thiserror::Error;
+
+/// Capture failures that callers need to tell apart from generic I/O errors.
+#[derive(Debug, Error)]
+pub enum CaptureError {
+    #[error("Screen capture permission denied: {0}")]
+    PermissionDenied(String),
+}
+
+impl CaptureError {
+    /// Protocol error kind to report for a failed backend call.
+    #[must_use]
+    pub fn kind_of(e: &anyhow::Error) -> ErrorKind {
+        match e.downcast_ref::<Self>() {
+            Some(Self::PermissionDenied(_)) => ErrorKind::PermissionDenied,
+            None => ErrorKind::IoError,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_kind_survives_context() {
+        let denied: anyhow::Result<()> = Err(CaptureError::PermissionDenied("no".into()).into());
+        let e = denied.context("Failed to start").unwrap_err();
+        assert_eq!(CaptureError::kind_of(&e), ErrorKind::PermissionDenied);
+        assert_eq!(
+            CaptureError::kind_of(&anyhow::anyhow!("disk full")),
+            ErrorKind::IoError
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Capture backend abstraction shared by the platform recorders.
+//!
+//! Recorder binaries pick a `CaptureBackend` at runtime from the
+//! `CaptureBackendKind` in the start command. `SyntheticBackend` needs no
+//! display, so recorder logic can be unit-tested headlessly.
+
+#![allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+
+mod dedup;
+mod error;
+mod lock;
+mod meter;
+mod mp4;
+mod region;
+mod synthetic;
+mod timeline;
+
+pub use dedup::*;
+pub use error::*;
+pub use lock::*;
+pub use meter::*;
+pub use mp4::*;
+pub use region::*;
+pub use synthetic::*;
+pub use timeline::*;
+
+use anyhow::{bail, Result};
+use mandygif_protocol::{AudioOptions, CaptureRegion, FrameRateMode, RecordingQuality};
+use std::path::PathBuf;
+
+/// What to record and where to write it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureConfig {
+    /// Fixed screen area, or the size of `window` when it was picked.
+    pub region: CaptureRegion,
+    /// X11 window to follow instead of a fixed area.
+    pub window: Option<u64>,
+    pub fps: u32,
+    pub cursor: bool,
+    pub audio: AudioOptions,
+    pub quality: RecordingQuality,
+    pub frame_rate: FrameRateMode,
+    pub out: PathBuf,
+}
+
+impl CaptureConfig {
+    /// Reject configurations no backend can record.
+    ///
+    /// # Errors
+    /// Returns error if the region is empty or `fps` is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.region.width == 0 || self.region.height == 0 {
+            bail!("Invalid dimensions");
+        }
+        if self.fps == 0 {
+            bail!("Frame rate must be at least 1");
+        }
+        Ok(())
+    }
+
+    /// Rough size growth of the output, for disk-space warnings.
+    ///
+    /// Based on typical desktop content; busy video can exceed it several times.
+    #[must_use]
+    pub fn estimated_bytes_per_sec(&self) -> u64 {
+        // Bits per pixel per frame after compression
+        let bpp = match self.quality {
+            RecordingQuality::Draft => 0.05,
+            RecordingQuality::High => 0.12,
+            RecordingQuality::Lossless => 1.5,
+        };
+        let pixels = f64::from(self.region.width) * f64::from(self.region.height);
+        let video = pixels * f64::from(self.fps) * bpp / 8.0;
+
+        // AAC at 128 kbit/s per track
+        let tracks =
+            u64::from(self.audio.system.is_some()) + u64::from(self.audio.microphone.is_some());
+        video.round() as u64 + tracks * 16_000
+    }
+}
+
+/// Optional features a backend supports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    pub pause: bool,
+    /// Can draw (or omit) the pointer on request.
+    pub cursor: bool,
+    /// Can record `AudioOptions` sources.
+    pub audio: bool,
+    /// Can follow a window (`CaptureConfig::window`).
+    pub window: bool,
+}
+
+/// Running totals for the current recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CaptureStats {
+    /// Recorded time, excluding pauses.
+    pub duration_ms: u64,
+    pub frames: u64,
+    /// Frames the source failed to deliver on schedule.
+    pub dropped_frames: u64,
+}
+
+impl CaptureStats {
+    /// Frames per second actually delivered.
+    #[must_use]
+    pub fn actual_fps(&self) -> f32 {
+        if self.duration_ms == 0 {
+            return 0.0;
+        }
+        (self.frames as f64 * 1000.0 / self.duration_ms as f64) as f32
+    }
+}
+
+/// A screen capture implementation.
+///
+/// Lifecycle is `start`, any number of `pause`/`resume` pairs, then `stop`.
+/// Out-of-order calls return an error rather than panicking.
+pub trait CaptureBackend: Send {
+    /// Short identifier used in logs.
+    fn name(&self) -> &'static str;
+
+    fn capabilities(&self) -> Capabilities;
+
+    /// Begin recording.
+    ///
+    /// # Errors
+    /// Returns error if already started or the capture cannot be set up.
+    fn start(&mut self, config: &CaptureConfig) -> Result<()>;
+
+    /// Suspend capture without ending the file.
+    ///
+    /// # Errors
+    /// Returns error if not recording or pausing is unsupported.
+    fn pause(&mut self) -> Result<()>;
+
+    /// Continue a paused capture.
+    ///
+    /// # Errors
+    /// Returns error if not paused.
+    fn resume(&mut self) -> Result<()>;
+
+    /// Finish recording and finalize the output.
+    ///
+    /// # Errors
+    /// Returns error if not started or the output cannot be finalized.
+    fn stop(&mut self) -> Result<CaptureStats>;
+
+    fn stats(&self) -> CaptureStats;
+
+    /// Report a capture that broke down while running.
+    ///
+    /// # Errors
+    /// Returns the failure, if any. What was recorded can still be kept by
+    /// calling `stop`.
+    fn check(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::AudioSource;
+
+    #[test]
+    fn test_size_estimate_scales_with_quality() {
+        let mut config = CaptureConfig {
+            region: CaptureRegion {
+                x: 0,
+                y: 0,
+                width: 1280,
+                height: 720,
+            },
+            window: None,
+            fps: 30,
+            cursor: false,
+            audio: AudioOptions::default(),
+            quality: RecordingQuality::High,
+            frame_rate: FrameRateMode::Constant,
+            out: PathBuf::from("/tmp/clip.mp4"),
+        };
+        assert_eq!(config.estimated_bytes_per_sec(), 414_720);
+
+        config.audio.microphone = Some(AudioSource::default());
+        assert_eq!(config.estimated_bytes_per_sec(), 430_720);
+
+        let high = config.estimated_bytes_per_sec();
+        config.quality = RecordingQuality::Lossless;
+        assert!(config.estimated_bytes_per_sec() > 10 * high);
+    }
+}
@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use mandygif_protocol::recording_lock_path;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Marks a recording as in progress until it is finalized.
+///
+/// Deliberately not released on drop: a recording abandoned without
+/// `release` is unfinished and should be recovered like a crashed one.
+#[derive(Debug)]
+pub struct RecordingLock {
+    path: PathBuf,
+}
+
+impl RecordingLock {
+    /// Mark `recording` as owned by this process.
+    ///
+    /// # Errors
+    /// Returns error if the lock file cannot be written.
+    pub fn acquire(recording: &Path) -> Result<Self> {
+        let path = recording_lock_path(recording);
+        fs::write(&path, std::process::id().to_string())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(Self { path })
+    }
+
+    /// The recording is complete; remove the marker.
+    ///
+    /// # Errors
+    /// Returns error if the lock file cannot be removed.
+    pub fn release(self) -> Result<()> {
+        fs::remove_file(&self.path)
+            .with_context(|| format!("Failed to remove {}", self.path.display()))
+    }
+
+    /// Whether `recording` was left unfinished by a process that is gone.
+    #[must_use]
+    pub fn is_stale(recording: &Path) -> bool {
+        let Ok(owner) = fs::read_to_string(recording_lock_path(recording)) else {
+            return false;
+        };
+        owner
+            .trim()
+            .parse()
+            .map_or(true, |pid| !process_running(pid))
+    }
+
+    /// Forget about a recording that has been recovered or given up on.
+    ///
+    /// # Errors
+    /// Returns error if an existing lock file cannot be removed.
+    pub fn clear(recording: &Path) -> Result<()> {
+        match fs::remove_file(recording_lock_path(recording)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn process_running(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    // Only Linux has a recorder today; elsewhere treat every owner as gone
+    cfg!(target_os = "linux") && Path::new("/proc").join(pid.to_string()).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_lifecycle() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let clip = dir.path().join("clip.mp4");
+        assert!(!RecordingLock::is_stale(&clip));
+
+        // Held by a live process (us)
+        let lock = RecordingLock::acquire(&clip)?;
+        assert!(!RecordingLock::is_stale(&clip));
+        lock.release()?;
+        assert!(!RecordingLock::is_stale(&clip));
+
+        // Left behind by a process that no longer exists
+        fs::write(recording_lock_path(&clip), u32::MAX.to_string())?;
+        assert!(RecordingLock::is_stale(&clip));
+        RecordingLock::clear(&clip)?;
+        RecordingLock::clear(&clip)?;
+        assert!(!RecordingLock::is_stale(&clip));
+        Ok(())
+    }
+}
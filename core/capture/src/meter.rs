@@ -0,0 +1,108 @@
+use crate::CaptureStats;
+
+/// Derives recording statistics from the presentation timestamps of the
+/// frames that actually reached the encoder.
+///
+/// Wall-clock time overstates the recording when the source is slow to
+/// start and hides frames the source failed to deliver; PTS does neither.
+#[derive(Debug, Clone)]
+pub struct FrameMeter {
+    interval_ns: u64,
+    /// Longest expected gap; beyond the frame interval for variable rates.
+    max_gap_ns: u64,
+    first_ns: Option<u64>,
+    last_ns: u64,
+    frames: u64,
+    dropped: u64,
+}
+
+impl FrameMeter {
+    /// Meter for a source running at `fps`.
+    #[must_use]
+    pub fn new(fps: u32) -> Self {
+        let interval_ns = 1_000_000_000 / u64::from(fps.max(1));
+        Self {
+            interval_ns,
+            max_gap_ns: interval_ns,
+            first_ns: None,
+            last_ns: 0,
+            frames: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Meter for a variable rate source that skips unchanged frames but
+    /// sends one at least every `max_gap_ms`.
+    #[must_use]
+    pub fn variable(fps: u32, max_gap_ms: u32) -> Self {
+        let mut meter = Self::new(fps);
+        meter.max_gap_ns = meter.interval_ns.max(u64::from(max_gap_ms) * 1_000_000);
+        meter
+    }
+
+    /// Account for a frame stamped `pts_ns`.
+    pub fn record(&mut self, pts_ns: u64) {
+        if self.first_ns.is_none() {
+            self.first_ns = Some(pts_ns);
+        } else {
+            // Allow half a frame of jitter before calling a gap a drop
+            let late = pts_ns.saturating_sub(self.last_ns + self.max_gap_ns);
+            if late * 2 > self.interval_ns {
+                self.dropped += (late + self.interval_ns / 2) / self.interval_ns;
+            }
+        }
+        self.last_ns = self.last_ns.max(pts_ns);
+        self.frames += 1;
+    }
+
+    /// Totals so far. The last frame counts for one full interval.
+    #[must_use]
+    pub fn stats(&self) -> CaptureStats {
+        let duration_ns = self
+            .first_ns
+            .map_or(0, |first| self.last_ns - first + self.interval_ns);
+        CaptureStats {
+            duration_ms: duration_ns / 1_000_000,
+            frames: self.frames,
+            dropped_frames: self.dropped,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    #[test]
+    fn test_counts_gaps_as_dropped_frames() {
+        let mut meter = FrameMeter::new(10);
+        assert_eq!(meter.stats(), CaptureStats::default());
+
+        // Starts late, jitters, then skips frames at 300 and 400 ms
+        for pts in [250, 352, 448, 550, 850, 950] {
+            meter.record(pts * MS);
+        }
+
+        let stats = meter.stats();
+        assert_eq!(stats.frames, 6);
+        assert_eq!(stats.dropped_frames, 2);
+        assert_eq!(stats.duration_ms, 800);
+        assert!((stats.actual_fps() - 7.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_variable_rate_gaps_are_not_drops() {
+        let mut meter = FrameMeter::variable(10, 500);
+        // Idle until a forced repeat at 500 ms, then a late one at 1200 ms
+        for pts in [0, 100, 600, 1200] {
+            meter.record(pts * MS);
+        }
+
+        let stats = meter.stats();
+        assert_eq!(stats.frames, 4);
+        assert_eq!(stats.dropped_frames, 1);
+        assert_eq!(stats.duration_ms, 1300);
+    }
+}
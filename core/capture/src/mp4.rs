@@ -0,0 +1,164 @@
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largest `moov` box we are willing to load; real ones are a few KiB.
+const MAX_MOOV_BYTES: u64 = 64 << 20;
+
+/// Media duration recorded in a finalized MP4's movie header.
+///
+/// # Errors
+/// Returns error if the file cannot be read or has no `moov`/`mvhd` box.
+pub fn mp4_duration_ms(path: &Path) -> Result<u64> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    read_duration_ms(&mut BufReader::new(file))
+}
+
+fn read_duration_ms<R: Read + Seek>(reader: &mut R) -> Result<u64> {
+    let moov = read_top_level(reader, *b"moov")?.context("No moov box; file not finalized")?;
+    let mvhd = find_child(&moov, *b"mvhd").context("No mvhd box in moov")?;
+    parse_mvhd(mvhd)
+}
+
+/// Body of the first top-level box of type `kind`, skipping others unread.
+fn read_top_level<R: Read + Seek>(reader: &mut R, kind: [u8; 4]) -> Result<Option<Vec<u8>>> {
+    let end = reader.seek(SeekFrom::End(0))?;
+    let mut pos = reader.seek(SeekFrom::Start(0))?;
+
+    while pos + 8 <= end {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        let (mut size, found) = split_header(&header);
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            reader.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = end - pos;
+        }
+        if size < header_len || pos + size > end {
+            bail!("Malformed MP4 box at offset {pos}");
+        }
+
+        if found == kind {
+            let len = size - header_len;
+            if len > MAX_MOOV_BYTES {
+                bail!("MP4 box at offset {pos} is too large");
+            }
+            let mut body = vec![0u8; usize::try_from(len)?];
+            reader.read_exact(&mut body)?;
+            return Ok(Some(body));
+        }
+        pos = reader.seek(SeekFrom::Start(pos + size))?;
+    }
+    Ok(None)
+}
+
+/// Body of the first child box of type `kind` within an in-memory container.
+fn find_child(mut data: &[u8], kind: [u8; 4]) -> Option<&[u8]> {
+    while data.len() >= 8 {
+        let (size, found) = split_header(data[..8].try_into().ok()?);
+        let size = usize::try_from(size).ok()?;
+        if size < 8 || size > data.len() {
+            return None;
+        }
+        if found == kind {
+            return Some(&data[8..size]);
+        }
+        data = &data[size..];
+    }
+    None
+}
+
+fn split_header(header: &[u8; 8]) -> (u64, [u8; 4]) {
+    let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+    (
+        u64::from(size),
+        [header[4], header[5], header[6], header[7]],
+    )
+}
+
+fn parse_mvhd(body: &[u8]) -> Result<u64> {
+    let be_u32 = |at: usize| -> Result<u64> {
+        let bytes = body.get(at..at + 4).context("Truncated mvhd box")?;
+        Ok(u64::from(u32::from_be_bytes(bytes.try_into()?)))
+    };
+    let be_u64 = |at: usize| -> Result<u64> {
+        let bytes = body.get(at..at + 8).context("Truncated mvhd box")?;
+        Ok(u64::from_be_bytes(bytes.try_into()?))
+    };
+
+    // version(1) flags(3), then creation/modification times sized by version
+    let (timescale, duration) = match body.first() {
+        Some(0) => (be_u32(12)?, be_u32(16)?),
+        Some(1) => (be_u32(20)?, be_u64(24)?),
+        _ => bail!("Unsupported mvhd version"),
+    };
+    if timescale == 0 {
+        bail!("mvhd timescale is zero");
+    }
+    Ok(duration.saturating_mul(1000) / timescale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn mp4_box(kind: &[u8; 4], body: &[u8]) -> Vec<u8> {
+        let size = u32::try_from(body.len() + 8).unwrap_or(u32::MAX);
+        [&size.to_be_bytes()[..], kind, body].concat()
+    }
+
+    fn mvhd(version: u8, timescale: u32, duration: u64) -> Vec<u8> {
+        let mut body = vec![version, 0, 0, 0];
+        if version == 0 {
+            body.extend_from_slice(&[0; 8]);
+            body.extend_from_slice(&timescale.to_be_bytes());
+            body.extend_from_slice(&u32::try_from(duration).unwrap_or(0).to_be_bytes());
+        } else {
+            body.extend_from_slice(&[0; 16]);
+            body.extend_from_slice(&timescale.to_be_bytes());
+            body.extend_from_slice(&duration.to_be_bytes());
+        }
+        body.extend_from_slice(&[0; 80]);
+        mp4_box(b"mvhd", &body)
+    }
+
+    fn file(mvhd: &[u8]) -> Cursor<Vec<u8>> {
+        let moov = mp4_box(
+            b"moov",
+            &[mp4_box(b"udta", &[0; 12]), mvhd.to_vec()].concat(),
+        );
+        let bytes = [
+            mp4_box(b"ftyp", b"isom\0\0\0\0"),
+            mp4_box(b"mdat", &[0xAB; 300]),
+            moov,
+        ]
+        .concat();
+        Cursor::new(bytes)
+    }
+
+    #[test]
+    fn test_reads_movie_duration() -> Result<()> {
+        assert_eq!(read_duration_ms(&mut file(&mvhd(0, 1000, 2533)))?, 2533);
+        assert_eq!(
+            read_duration_ms(&mut file(&mvhd(1, 90_000, 180_000)))?,
+            2000
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unfinalized_file_is_an_error() {
+        let bytes = [
+            mp4_box(b"ftyp", b"isom\0\0\0\0"),
+            mp4_box(b"mdat", &[0; 64]),
+        ]
+        .concat();
+        assert!(read_duration_ms(&mut Cursor::new(bytes)).is_err());
+    }
+}
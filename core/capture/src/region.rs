@@ -0,0 +1,167 @@
+//! Rectangle arithmetic on screen regions, in physical pixels.
+
+use mandygif_protocol::CaptureRegion;
+
+/// The part of `a` that is also in `b`; empty (zero-sized) if none.
+#[must_use]
+pub fn intersection(a: &CaptureRegion, b: &CaptureRegion) -> CaptureRegion {
+    let left = a.x.max(b.x);
+    let top = a.y.max(b.y);
+    let right = right(a).min(right(b));
+    let bottom = bottom(a).min(bottom(b));
+    let width = u32::try_from(right - i64::from(left)).unwrap_or(0);
+    let height = u32::try_from(bottom - i64::from(top)).unwrap_or(0);
+    let (width, height) = if width == 0 || height == 0 {
+        (0, 0)
+    } else {
+        (width, height)
+    };
+    CaptureRegion {
+        x: left,
+        y: top,
+        width,
+        height,
+    }
+}
+
+/// The largest part of `region` that lies on `monitors`.
+///
+/// Monitors of different sizes leave dead space beside the smaller ones,
+/// which no rectangle around them all avoids; this picks the biggest
+/// rectangle entirely on screen instead. Empty if `region` misses every
+/// monitor.
+#[must_use]
+pub fn on_monitors(region: &CaptureRegion, monitors: &[CaptureRegion]) -> CaptureRegion {
+    let parts: Vec<CaptureRegion> = monitors
+        .iter()
+        .map(|m| intersection(region, m))
+        .filter(|part| part.width > 0)
+        .collect();
+
+    // Every edge of every visible part cuts the region into a grid whose
+    // cells are either wholly on a monitor or wholly off
+    let lines = |edges: fn(&CaptureRegion) -> [i64; 2]| {
+        let mut lines: Vec<i64> = parts.iter().flat_map(edges).collect();
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    };
+    let xs = lines(|p| [i64::from(p.x), right(p)]);
+    let ys = lines(|p| [i64::from(p.y), bottom(p)]);
+    let on_screen: Vec<Vec<bool>> = xs
+        .iter()
+        .map(|&x| {
+            ys.iter()
+                .map(|&y| {
+                    parts.iter().any(|p| {
+                        (i64::from(p.x)..right(p)).contains(&x)
+                            && (i64::from(p.y)..bottom(p)).contains(&y)
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut best = (0, CaptureRegion::default());
+    for left in 0..xs.len() {
+        for right in left + 1..xs.len() {
+            for top in 0..ys.len() {
+                for bottom in top + 1..ys.len() {
+                    let covered = (left..right).all(|i| (top..bottom).all(|j| on_screen[i][j]));
+                    let (width, height) = (xs[right] - xs[left], ys[bottom] - ys[top]);
+                    if covered && width * height > best.0 {
+                        best = (
+                            width * height,
+                            CaptureRegion {
+                                x: i32::try_from(xs[left]).unwrap_or(region.x),
+                                y: i32::try_from(ys[top]).unwrap_or(region.y),
+                                width: u32::try_from(width).unwrap_or(0),
+                                height: u32::try_from(height).unwrap_or(0),
+                            },
+                        );
+                    }
+                }
+            }
+        }
+    }
+    best.1
+}
+
+/// `region` trimmed to the even dimensions H.264 needs.
+#[must_use]
+pub fn even_size(region: &CaptureRegion) -> CaptureRegion {
+    CaptureRegion {
+        width: region.width & !1,
+        height: region.height & !1,
+        ..region.clone()
+    }
+}
+
+/// Whether pixel (`x`, `y`) lies in `region`.
+#[must_use]
+pub fn contains(region: &CaptureRegion, x: i32, y: i32) -> bool {
+    let (dx, dy) = (
+        i64::from(x) - i64::from(region.x),
+        i64::from(y) - i64::from(region.y),
+    );
+    (0..i64::from(region.width)).contains(&dx) && (0..i64::from(region.height)).contains(&dy)
+}
+
+fn right(region: &CaptureRegion) -> i64 {
+    i64::from(region.x) + i64::from(region.width)
+}
+
+fn bottom(region: &CaptureRegion) -> i64 {
+    i64::from(region.y) + i64::from(region.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> CaptureRegion {
+        CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_monitors_left_of_the_primary() {
+        // A 1.5x laptop panel left of a 1080p primary, bottoms aligned
+        let monitors = [rect(-2880, -720, 2880, 1800), rect(0, 0, 1920, 1080)];
+
+        // Hanging off the left edge of the desktop
+        let window = rect(-3000, 100, 401, 301);
+        let on_screen = on_monitors(&window, &monitors);
+        assert_eq!(on_screen, rect(-2880, 100, 281, 301));
+        assert_eq!(even_size(&on_screen), rect(-2880, 100, 280, 300));
+        assert!(contains(&on_screen, -2880, 400));
+        assert!(!contains(&on_screen, -2881, 100));
+
+        // Entirely off screen
+        let gone = on_monitors(&rect(5000, 0, 100, 100), &monitors);
+        assert_eq!((gone.width, gone.height), (0, 0));
+        assert_eq!(on_monitors(&window, &[]), CaptureRegion::default());
+    }
+
+    #[test]
+    fn test_mixed_size_monitors() {
+        // A 1440p monitor left of a 1080p one, tops aligned: the space
+        // below the smaller one is not on any screen
+        let monitors = [rect(0, 0, 2560, 1440), rect(2560, 0, 1920, 1080)];
+
+        // Mostly on the big monitor: its part there is the largest
+        let region = rect(2000, 900, 1000, 400);
+        assert_eq!(on_monitors(&region, &monitors), rect(2000, 900, 560, 400));
+        // Mostly on the small one: the strip across both wins
+        let region = rect(2400, 100, 1200, 1000);
+        assert_eq!(on_monitors(&region, &monitors), rect(2400, 100, 1200, 980));
+
+        // Within the monitors' union, nothing is cut
+        let inside = rect(2000, 100, 1000, 800);
+        assert_eq!(on_monitors(&inside, &monitors), inside);
+    }
+}
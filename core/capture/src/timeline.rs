@@ -0,0 +1,157 @@
+use anyhow::{bail, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Millisecond time source, injectable so tests need not sleep.
+pub trait Clock: Send + Sync {
+    fn now_ms(&self) -> u64;
+}
+
+/// Wall-clock time since creation.
+pub struct SystemClock(Instant);
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self(Instant::now())
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        u64::try_from(self.0.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+/// Clock that only moves when told to. Clones share the same time.
+#[derive(Clone, Default)]
+pub struct ManualClock(Arc<AtomicU64>);
+
+impl ManualClock {
+    pub fn advance(&self, ms: u64) {
+        self.0.fetch_add(ms, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Recording state machine that keeps paused time out of the duration.
+#[derive(Debug, Default)]
+pub struct Timeline {
+    started_at: Option<u64>,
+    paused_at: Option<u64>,
+    stopped_at: Option<u64>,
+    paused_total: u64,
+}
+
+impl Timeline {
+    /// Begin a fresh recording, discarding any previous one.
+    ///
+    /// # Errors
+    /// Returns error if a recording is in progress.
+    pub fn start(&mut self, now: u64) -> Result<()> {
+        if self.is_running() {
+            bail!("Already recording");
+        }
+        *self = Self {
+            started_at: Some(now),
+            ..Self::default()
+        };
+        Ok(())
+    }
+
+    /// # Errors
+    /// Returns error if not recording or already paused.
+    pub fn pause(&mut self, now: u64) -> Result<()> {
+        self.ensure_running()?;
+        if self.paused_at.is_some() {
+            bail!("Already paused");
+        }
+        self.paused_at = Some(now);
+        Ok(())
+    }
+
+    /// # Errors
+    /// Returns error if not paused.
+    pub fn resume(&mut self, now: u64) -> Result<()> {
+        self.ensure_running()?;
+        let Some(at) = self.paused_at.take() else {
+            bail!("Not paused");
+        };
+        self.paused_total += now.saturating_sub(at);
+        Ok(())
+    }
+
+    /// Freeze the duration; a pause in progress ends here.
+    ///
+    /// # Errors
+    /// Returns error if not recording.
+    pub fn stop(&mut self, now: u64) -> Result<()> {
+        self.ensure_running()?;
+        if let Some(at) = self.paused_at.take() {
+            self.paused_total += now.saturating_sub(at);
+        }
+        self.stopped_at = Some(now);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some() && self.stopped_at.is_none()
+    }
+
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Recorded time at `now`, excluding pauses.
+    #[must_use]
+    pub fn elapsed_ms(&self, now: u64) -> u64 {
+        let Some(start) = self.started_at else {
+            return 0;
+        };
+        let end = self.paused_at.or(self.stopped_at).unwrap_or(now);
+        end.saturating_sub(start).saturating_sub(self.paused_total)
+    }
+
+    fn ensure_running(&self) -> Result<()> {
+        if !self.is_running() {
+            bail!("Not recording");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pauses_are_excluded() -> Result<()> {
+        let mut t = Timeline::default();
+        t.start(100)?;
+        t.pause(1100)?;
+        assert_eq!(t.elapsed_ms(5000), 1000);
+        t.resume(2100)?;
+        assert_eq!(t.elapsed_ms(2600), 1500);
+        t.pause(3100)?;
+        t.stop(9000)?;
+        assert_eq!(t.elapsed_ms(20_000), 2000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_order_calls_fail() {
+        let mut t = Timeline::default();
+        assert!(t.pause(0).is_err());
+        assert!(t.stop(0).is_err());
+        assert!(t.start(0).is_ok());
+        assert!(t.resume(0).is_err());
+        assert!(t.start(0).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_sign_loss)]
+
+mod px_rect;
+
+use crate::graph::FilterGraph;
+use crate::overlay::still_source;
+use crate::sprite;
+use anyhow::Result;
+use mandygif_captions::{ff_color, parse_hex_color};
+use mandygif_protocol::{Annotation, AnnotationShape};
+use px_rect::PxRect;
+use std::path::Path;
+
+/// Output frame the annotations are drawn on.
+pub struct Canvas<'a> {
+    pub size: (u32, u32),
+    pub fps: u32,
+    /// Scratch directory for rasterized sprites; must outlive the ffmpeg run.
+    pub workdir: &'a Path,
+}
+
+/// Draw an annotation onto the main chain.
+pub fn draw(g: &mut FilterGraph, ann: &Annotation, canvas: &Canvas) -> Result<()> {
+    let enable = format!(
+        "enable='between(t,{:.3},{:.3})'",
+        ann.start_ms as f64 / 1000.0,
+        ann.end_ms as f64 / 1000.0
+    );
+
+    match ann.shape {
+        AnnotationShape::Rect => {
+            let r = PxRect::from_rect(&ann.rect, canvas.size, 1);
+            if !r.is_empty() {
+                g.push(format!(
+                    "drawbox={}:color={}:t={}:{enable}",
+                    r.drawbox(),
+                    ff_color(&ann.color, 1.0)?,
+                    ann.stroke.max(1)
+                ));
+            }
+        }
+        AnnotationShape::Highlight => {
+            let r = PxRect::from_rect(&ann.rect, canvas.size, 1);
+            for dim in r.surroundings(canvas.size) {
+                g.push(format!(
+                    "drawbox={}:color=black@0.6:t=fill:{enable}",
+                    dim.drawbox()
+                ));
+            }
+        }
+        AnnotationShape::Pixelate | AnnotationShape::Blur => {
+            // Align outward to even pixels so chroma subsampling can't leave a seam.
+            let r = PxRect::from_rect(&ann.rect, canvas.size, 2);
+            if r.is_empty() {
+                return Ok(());
+            }
+            let effect = if ann.shape == AnnotationShape::Pixelate {
+                let block = (canvas.size.0 / 40).max(6);
+                format!("pixelize=w={block}:h={block}")
+            } else {
+                let sigma = (r.w.max(r.h) as f32 / 8.0).clamp(6.0, 40.0);
+                format!("gblur=sigma={sigma:.1}:steps=3")
+            };
+            let base = g.cut();
+            let (keep, src, patch) = (g.label("keep"), g.label("src"), g.label("patch"));
+            g.side(format!("[{base}]split[{keep}][{src}]"));
+            g.side(format!(
+                "[{src}]crop={}:{}:{}:{},{effect}[{patch}]",
+                r.w, r.h, r.x, r.y
+            ));
+            g.push(format!("[{keep}][{patch}]overlay={}:{}:{enable}", r.x, r.y));
+        }
+        AnnotationShape::Arrow | AnnotationShape::Ellipse => {
+            let path = canvas.workdir.join(format!("{}.png", g.label("sprite")));
+            sprite::render(ann, canvas.size, parse_hex_color(&ann.color, 1.0)?, &path)?;
+            let base = g.cut();
+            let spr = g.label("spr");
+            g.side(format!("{}[{spr}]", still_source(&path, canvas.fps)?));
+            g.push(format!("[{base}][{spr}]overlay=0:0:shortest=1:{enable}"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::CaptionRect;
+
+    fn annotation(
+        shape: AnnotationShape,
+        rect: (f32, f32, f32, f32),
+        ms: (u64, u64),
+    ) -> Annotation {
+        Annotation {
+            shape,
+            rect: CaptionRect {
+                x: rect.0,
+                y: rect.1,
+                w: rect.2,
+                h: rect.3,
+            },
+            start_ms: ms.0,
+            end_ms: ms.1,
+            color: "#FF3B30".into(),
+            stroke: 4,
+        }
+    }
+
+    #[test]
+    fn test_redactions_chain_one_after_another() -> Result<()> {
+        let canvas = Canvas {
+            size: (640, 360),
+            fps: 15,
+            workdir: Path::new("/tmp"),
+        };
+        let mut g = FilterGraph::default();
+        g.push("fps=15");
+        for ann in [
+            annotation(
+                AnnotationShape::Pixelate,
+                (0.25, 0.5, 0.25, 0.25),
+                (0, 2000),
+            ),
+            annotation(AnnotationShape::Blur, (0.5, 0.0, 0.5, 0.5), (1000, 3000)),
+        ] {
+            draw(&mut g, &ann, &canvas)?;
+        }
+
+        // Each patch is cut from what the previous one left
+        assert_eq!(
+            g.finish(),
+            "fps=15[v1];\
+             [v1]split[keep2][src3];\
+             [src3]crop=160:90:160:180,pixelize=w=16:h=16[patch4];\
+             [keep2][patch4]overlay=160:180:enable='between(t,0.000,2.000)'[v5];\
+             [v5]split[keep6][src7];\
+             [src7]crop=320:180:320:0,gblur=sigma=40.0:steps=3[patch8];\
+             [keep6][patch8]overlay=320:0:enable='between(t,1.000,3.000)'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sprites_show_only_while_annotated() -> Result<()> {
+        let workdir = tempfile::tempdir()?;
+        let canvas = Canvas {
+            size: (320, 180),
+            fps: 15,
+            workdir: workdir.path(),
+        };
+        let mut g = FilterGraph::default();
+        g.push("fps=15");
+        draw(
+            &mut g,
+            &annotation(AnnotationShape::Arrow, (0.1, 0.1, 0.5, 0.5), (1500, 4250)),
+            &canvas,
+        )?;
+        draw(
+            &mut g,
+            &annotation(AnnotationShape::Ellipse, (0.6, 0.2, 0.3, 0.3), (0, 500)),
+            &canvas,
+        )?;
+
+        let filter = g.finish();
+        for sprite in ["sprite1.png", "sprite4.png"] {
+            assert!(workdir.path().join(sprite).exists(), "{sprite} not drawn");
+        }
+        assert!(filter.contains("[v2][spr3]overlay=0:0:shortest=1:enable='between(t,1.500,4.250)'"));
+        assert!(
+            filter.ends_with("[v5][spr6]overlay=0:0:shortest=1:enable='between(t,0.000,0.500)'")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_sign_loss)]
+
+use mandygif_protocol::CaptionRect;
+
+/// Pixel rectangle clamped to the frame.
+pub(super) struct PxRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl PxRect {
+    /// Convert a normalized rect, rounding outward to multiples of `align`.
+    pub fn from_rect(rect: &CaptionRect, size: (u32, u32), align: u32) -> Self {
+        let (fw, fh) = (size.0 as f32, size.1 as f32);
+        let span = |a: f32, b: f32, full: f32, max: u32| {
+            let lo = (a.min(b) * full).floor().clamp(0.0, full) as u32;
+            let hi = (a.max(b) * full).ceil().clamp(0.0, full) as u32;
+            let lo = lo / align * align;
+            let hi = hi.div_ceil(align) * align;
+            (lo, hi.min(max / align * align))
+        };
+        let (x0, x1) = span(rect.x, rect.x + rect.w, fw, size.0);
+        let (y0, y1) = span(rect.y, rect.y + rect.h, fh, size.1);
+        Self {
+            x: x0,
+            y: y0,
+            w: x1.saturating_sub(x0),
+            h: y1.saturating_sub(y0),
+        }
+    }
+
+    // drawbox treats a zero size as "whole frame", so empty rects must be skipped.
+    pub fn is_empty(&self) -> bool {
+        self.w == 0 || self.h == 0
+    }
+
+    pub fn drawbox(&self) -> String {
+        format!("x={}:y={}:w={}:h={}", self.x, self.y, self.w, self.h)
+    }
+
+    /// The four bands around this rect (above, below, left, right).
+    pub fn surroundings(&self, size: (u32, u32)) -> Vec<Self> {
+        let (right, bottom) = (self.x + self.w, self.y + self.h);
+        [
+            Self {
+                x: 0,
+                y: 0,
+                w: size.0,
+                h: self.y,
+            },
+            Self {
+                x: 0,
+                y: bottom,
+                w: size.0,
+                h: size.1.saturating_sub(bottom),
+            },
+            Self {
+                x: 0,
+                y: self.y,
+                w: self.x,
+                h: self.h,
+            },
+            Self {
+                x: right,
+                y: self.y,
+                w: size.0.saturating_sub(right),
+                h: self.h,
+            },
+        ]
+        .into_iter()
+        .filter(|r| !r.is_empty())
+        .collect()
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::{bail, Context, Result};
+use mandygif_protocol::ExportAudio;
+use std::path::Path;
+use std::process::Command;
+
+/// Loudness target for normalized exports (EBU R128 streaming levels).
+const LOUDNORM: &str = "loudnorm=I=-16:TP=-1.5:LRA=11,aresample=48000";
+
+/// Count the audio tracks in a recording.
+///
+/// # Errors
+/// Returns error if ffprobe is missing or cannot read the file.
+pub fn probe_tracks(input: &Path) -> Result<usize> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "a"])
+        .args(["-show_entries", "stream=index", "-of", "csv=p=0"])
+        .arg(input)
+        .output()
+        .context("ffprobe not found - please install ffmpeg")?;
+
+    if !output.status.success() {
+        bail!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .count())
+}
+
+/// ffmpeg output arguments carrying `tracks` audio tracks of input 0 into
+/// an MP4. Tracks (one per recorded source) are mixed into one.
+#[must_use]
+pub fn mp4_args(tracks: usize, mode: ExportAudio) -> Vec<String> {
+    if tracks == 0 || mode == ExportAudio::Mute {
+        return vec!["-an".into()];
+    }
+
+    let mut chain = Vec::new();
+    if tracks > 1 {
+        // normalize=0 keeps each source at its recorded gain
+        chain.push(format!("amix=inputs={tracks}:duration=longest:normalize=0"));
+    }
+    if mode == ExportAudio::Normalize {
+        chain.push(LOUDNORM.to_string());
+    }
+
+    let mut args = if chain.is_empty() {
+        vec!["-map".into(), "0:a:0".into()]
+    } else {
+        let inputs: String = (0..tracks).map(|i| format!("[0:a:{i}]")).collect();
+        vec![
+            "-filter_complex".into(),
+            format!("{inputs}{}[aout]", chain.join(",")),
+            "-map".into(),
+            "[aout]".into(),
+        ]
+    };
+    args.extend(["-c:a", "aac", "-b:a", "160k"].map(String::from));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mp4_audio_args() {
+        assert_eq!(mp4_args(0, ExportAudio::Normalize), ["-an"]);
+        assert_eq!(mp4_args(2, ExportAudio::Mute), ["-an"]);
+        assert_eq!(
+            mp4_args(1, ExportAudio::Keep),
+            ["-map", "0:a:0", "-c:a", "aac", "-b:a", "160k"]
+        );
+
+        let mixed = mp4_args(2, ExportAudio::Normalize);
+        assert_eq!(mixed[0], "-filter_complex");
+        assert_eq!(
+            mixed[1],
+            format!("[0:a:0][0:a:1]amix=inputs=2:duration=longest:normalize=0,{LOUDNORM}[aout]")
+        );
+        assert_eq!(mixed[2..4], ["-map", "[aout]"]);
+    }
+}
@@ -0,0 +1,198 @@
+#![allow(clippy::cast_precision_loss)]
+
+use mandygif_protocol::{CameraKeyframe, CaptionRect, Easing};
+
+/// Build a `zoompan` filter following the camera keyframes over a `size`
+/// source. Output keeps the source size; the regular scale step follows.
+pub fn zoompan(keys: &[CameraKeyframe], size: (u32, u32), fps: u32) -> String {
+    let keys = views(keys);
+
+    // Zoom 1/w shows a w-wide (and w-high) slice; the views already stay
+    // inside the frame, `clip` only guards against rounding.
+    let w = track(&keys, |k| f64::from(k.rect.w));
+    let x = track(&keys, |k| f64::from(k.rect.x));
+    let y = track(&keys, |k| f64::from(k.rect.y));
+
+    format!(
+        "zoompan=z='1/({w})':x='clip(iw*({x}),0,iw-iw/zoom)':y='clip(ih*({y}),0,ih-ih/zoom)':d=1:s={}x{}:fps={fps}",
+        size.0, size.1
+    )
+}
+
+/// Piecewise expression of the input time `it` interpolating `value`
+/// between keyframes.
+fn track(keys: &[CameraKeyframe], value: impl Fn(&CameraKeyframe) -> f64) -> String {
+    let Some(last) = keys.last() else {
+        return "1".to_string();
+    };
+    let mut expr = format!("{:.6}", value(last));
+
+    for pair in keys.windows(2).rev() {
+        let (from, to) = (&pair[0], &pair[1]);
+        let (t0, t1) = (secs(from.at_ms), secs(to.at_ms));
+        let (v0, v1) = (value(from), value(to));
+        let segment = if t1 > t0 {
+            let p = format!("((it-{t0:.3})/{:.3})", t1 - t0);
+            format!("{v0:.6}+({:.6})*{}", v1 - v0, ease(to.easing, &p))
+        } else {
+            format!("{v1:.6}")
+        };
+        expr = format!("if(lt(it,{t1:.3}),{segment},{expr})");
+    }
+
+    format!(
+        "if(lt(it,{:.3}),{:.6},{expr})",
+        secs(keys[0].at_ms),
+        value(&keys[0])
+    )
+}
+
+/// `keys` in time order, each rect widened to the view that shows it.
+///
+/// `zoompan` zooms both axes alike, so a view keeps the frame's aspect
+/// ratio: equal `w` and `h` as fractions of it. It is the smallest one
+/// holding the whole rect, centred on it and moved back inside the frame.
+fn views(keys: &[CameraKeyframe]) -> Vec<CameraKeyframe> {
+    let mut keys = keys.to_vec();
+    keys.sort_by_key(|k| k.at_ms);
+    for key in &mut keys {
+        let r = &key.rect;
+        let side = r.w.max(r.h).clamp(0.05, 1.0);
+        let centred = |at: f32, size: f32| (at + (size - side) / 2.0).clamp(0.0, 1.0 - side);
+        key.rect = CaptionRect {
+            x: centred(r.x, r.w),
+            y: centred(r.y, r.h),
+            w: side,
+            h: side,
+        };
+    }
+    keys
+}
+
+/// Visible source rect at `t_ms`, matching what `zoompan` renders.
+#[must_use]
+pub fn view_at(keys: &[CameraKeyframe], t_ms: u64) -> CaptionRect {
+    let keys = views(keys);
+
+    let lerp = |from: &CameraKeyframe, to: &CameraKeyframe| {
+        let p = (t_ms - from.at_ms) as f32 / (to.at_ms - from.at_ms) as f32;
+        let e = ease_value(to.easing, p);
+        let mix = |a: f32, b: f32| a + (b - a) * e;
+        CaptionRect {
+            x: mix(from.rect.x, to.rect.x),
+            y: mix(from.rect.y, to.rect.y),
+            w: mix(from.rect.w, to.rect.w),
+            h: mix(from.rect.h, to.rect.h),
+        }
+    };
+
+    // Views in between two inside the frame are inside it too
+    match keys.iter().position(|k| k.at_ms > t_ms) {
+        None => keys.last().map_or(FULL_FRAME, |k| k.rect.clone()),
+        Some(0) => keys[0].rect.clone(),
+        Some(i) => lerp(&keys[i - 1], &keys[i]),
+    }
+}
+
+const FULL_FRAME: CaptionRect = CaptionRect {
+    x: 0.0,
+    y: 0.0,
+    w: 1.0,
+    h: 1.0,
+};
+
+fn ease_value(easing: Easing, p: f32) -> f32 {
+    match easing {
+        Easing::Linear => p,
+        Easing::EaseIn => p * p,
+        Easing::EaseOut => p * (2.0 - p),
+        Easing::EaseInOut => p * p * (3.0 - 2.0 * p),
+        Easing::Hold => 0.0,
+    }
+}
+
+fn ease(easing: Easing, p: &str) -> String {
+    match easing {
+        Easing::Linear => p.to_string(),
+        Easing::EaseIn => format!("{p}*{p}"),
+        Easing::EaseOut => format!("{p}*(2-{p})"),
+        Easing::EaseInOut => format!("{p}*{p}*(3-2*{p})"),
+        Easing::Hold => "0".to_string(),
+    }
+}
+
+fn secs(ms: u64) -> f64 {
+    ms as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::CaptionRect;
+
+    fn key(at_ms: u64, x: f32, w: f32, easing: Easing) -> CameraKeyframe {
+        CameraKeyframe {
+            at_ms,
+            rect: CaptionRect { x, y: 0.0, w, h: w },
+            easing,
+        }
+    }
+
+    #[test]
+    fn test_single_keyframe_is_static() {
+        let expr = track(&[key(0, 0.25, 0.5, Easing::Linear)], |k| {
+            f64::from(k.rect.x)
+        });
+        assert_eq!(expr, "if(lt(it,0.000),0.250000,0.250000)");
+    }
+
+    #[test]
+    fn test_segments_are_ordered_by_time() {
+        let keys = [
+            key(2000, 0.5, 0.5, Easing::EaseInOut),
+            key(0, 0.0, 1.0, Easing::Linear),
+        ];
+        let filter = zoompan(&keys, (1280, 720), 30);
+        assert!(filter.contains(
+            "z='1/(if(lt(it,0.000),1.000000,if(lt(it,2.000),1.000000+(-0.500000)*((it-0.000)/2.000)*((it-0.000)/2.000)*(3-2*((it-0.000)/2.000)),0.500000)))'"
+        ));
+        assert!(filter.ends_with(":d=1:s=1280x720:fps=30"));
+    }
+
+    #[test]
+    fn test_non_square_rects_are_shown_whole() {
+        let rect = |x, y, w, h| CaptionRect { x, y, w, h };
+        let keys = [
+            // Wide: the height grows to the width around the same centre
+            CameraKeyframe {
+                at_ms: 0,
+                rect: rect(0.1, 0.6, 0.5, 0.2),
+                easing: Easing::Linear,
+            },
+            // Tall: the width grows to the height, then moves back inside
+            CameraKeyframe {
+                at_ms: 1000,
+                rect: rect(0.8, 0.1, 0.1, 0.6),
+                easing: Easing::Linear,
+            },
+        ];
+
+        let close = |got: CaptionRect, want: CaptionRect| {
+            let parts = |r: &CaptionRect| [r.x, r.y, r.w, r.h];
+            let near = parts(&got)
+                .iter()
+                .zip(parts(&want))
+                .all(|(a, b)| (a - b).abs() < 1e-6);
+            assert!(near, "{got:?} != {want:?}");
+        };
+        close(view_at(&keys, 0), rect(0.1, 0.45, 0.5, 0.5));
+        close(view_at(&keys, 1000), rect(0.4, 0.1, 0.6, 0.6));
+        close(view_at(&keys, 500), rect(0.25, 0.275, 0.55, 0.55));
+
+        // zoompan follows the same views
+        let filter = zoompan(&keys, (1280, 720), 30);
+        assert!(filter.contains("z='1/(if(lt(it,0.000),0.500000,"));
+        assert!(filter.contains(",0.600000)))'"));
+        assert!(filter.contains("y='clip(ih*(if(lt(it,0.000),0.450000,"));
+    }
+}
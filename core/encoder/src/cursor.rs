@@ -0,0 +1,198 @@
+//! Cursor-following camera and click emphasis from the recorder's cursor log.
+
+#![allow(clippy::cast_precision_loss)]
+
+mod follow;
+mod ripple;
+
+use anyhow::{bail, Context, Result};
+use mandygif_protocol::{
+    Annotation, CameraKeyframe, CaptureRegion, CursorTrack, CursorZoom, TrimRange,
+};
+use std::fs;
+
+/// Fold a cursor-follow request into the camera and annotation lists.
+///
+/// Camera keyframes are only generated when none were given explicitly;
+/// click ripples are placed relative to whichever camera is in effect.
+///
+/// # Errors
+/// Returns error if the cursor log cannot be read or parsed.
+pub fn apply(
+    zoom: Option<&CursorZoom>,
+    trim: &TrimRange,
+    camera: &mut Vec<CameraKeyframe>,
+    annotations: &mut Vec<Annotation>,
+) -> Result<()> {
+    let Some(zoom) = zoom else {
+        return Ok(());
+    };
+    if !zoom.track.exists() {
+        bail!(
+            "No cursor log at {}; only X11 recordings log the cursor",
+            zoom.track.display()
+        );
+    }
+    let text = fs::read_to_string(&zoom.track)
+        .with_context(|| format!("Failed to read cursor log {}", zoom.track.display()))?;
+    let track: CursorTrack = serde_json::from_str(&text).context("Invalid cursor log")?;
+    let clip = Clip::new(&track, trim);
+
+    if camera.is_empty() {
+        *camera = clip.follow(1.0 / zoom.zoom.max(1.0));
+    }
+    if zoom.emphasize_clicks {
+        annotations.extend(clip.ripples(camera));
+    }
+    Ok(())
+}
+
+/// Cursor events shifted to clip time and normalized to the capture region.
+struct Clip {
+    duration_ms: u64,
+    aspect: f32,
+    moves: Vec<(u64, (f32, f32))>,
+    clicks: Vec<(u64, (f32, f32))>,
+}
+
+impl Clip {
+    fn new(track: &CursorTrack, trim: &TrimRange) -> Self {
+        let r = &track.region;
+        // Relative to the area recorded at that moment, which follows a
+        // window as it moves
+        let norm = |t: u64, x: i32, y: i32| {
+            let r = region_at(track, t);
+            (
+                ((x - r.x) as f32 / r.width.max(1) as f32).clamp(0.0, 1.0),
+                ((y - r.y) as f32 / r.height.max(1) as f32).clamp(0.0, 1.0),
+            )
+        };
+        let in_clip = |t: u64| (trim.start_ms..=trim.end_ms).contains(&t);
+
+        Self {
+            duration_ms: trim.end_ms.saturating_sub(trim.start_ms),
+            aspect: r.width.max(1) as f32 / r.height.max(1) as f32,
+            moves: track
+                .samples
+                .iter()
+                .filter(|s| in_clip(s.t_ms))
+                .map(|s| (s.t_ms - trim.start_ms, norm(s.t_ms, s.x, s.y)))
+                .collect(),
+            clicks: track
+                .clicks
+                .iter()
+                .filter(|c| in_clip(c.t_ms))
+                .map(|c| (c.t_ms - trim.start_ms, norm(c.t_ms, c.x, c.y)))
+                .collect(),
+        }
+    }
+}
+
+/// Screen area being recorded at `t_ms`.
+fn region_at(track: &CursorTrack, t_ms: u64) -> &CaptureRegion {
+    track
+        .regions
+        .iter()
+        .take_while(|change| change.t_ms <= t_ms)
+        .last()
+        .map_or(&track.region, |change| &change.region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::{CursorClick, CursorSample, RegionChange};
+
+    #[test]
+    fn test_follow_zooms_in_then_out() {
+        let track = CursorTrack {
+            region: CaptureRegion {
+                x: 100,
+                y: 100,
+                width: 800,
+                height: 400,
+            },
+            samples: vec![CursorSample {
+                t_ms: 1000,
+                x: 900,
+                y: 500,
+            }],
+            clicks: vec![CursorClick {
+                t_ms: 1200,
+                x: 900,
+                y: 500,
+                button: 1,
+            }],
+            regions: Vec::new(),
+        };
+        let trim = TrimRange {
+            start_ms: 0,
+            end_ms: 5000,
+        };
+        let clip = Clip::new(&track, &trim);
+        let keys = clip.follow(0.5);
+
+        assert_eq!(keys[0].rect.w, 1.0);
+        let zoomed = keys.iter().find(|k| k.rect.w < 1.0).expect("zooms in");
+        // Bottom-right cursor pins the view to the corner
+        assert_eq!((zoomed.rect.x, zoomed.rect.y), (0.5, 0.5));
+        assert_eq!(keys.last().map(|k| k.rect.w), Some(1.0));
+
+        let ripples = clip.ripples(&keys);
+        assert_eq!(ripples.len(), 3);
+        assert!(ripples.iter().all(|a| a.rect.x + a.rect.w > 0.9));
+    }
+
+    #[test]
+    fn test_follows_a_moving_window() {
+        let at = |x, y| CaptureRegion {
+            x,
+            y,
+            width: 400,
+            height: 200,
+        };
+        let track = CursorTrack {
+            region: at(0, 0),
+            samples: vec![
+                CursorSample {
+                    t_ms: 100,
+                    x: 200,
+                    y: 100,
+                },
+                CursorSample {
+                    t_ms: 2000,
+                    x: 700,
+                    y: 400,
+                },
+            ],
+            clicks: Vec::new(),
+            regions: vec![RegionChange {
+                t_ms: 1000,
+                region: at(500, 300),
+            }],
+        };
+        let trim = TrimRange {
+            start_ms: 0,
+            end_ms: 3000,
+        };
+        let clip = Clip::new(&track, &trim);
+        // The same spot in the window, before and after it moved
+        assert_eq!(clip.moves[0].1, (0.5, 0.5));
+        assert_eq!(clip.moves[1].1, (0.5, 0.5));
+    }
+
+    #[test]
+    fn test_missing_log_is_an_error() {
+        let zoom = CursorZoom {
+            track: std::env::temp_dir().join("mandygif-no-such.cursor.json"),
+            zoom: 2.0,
+            emphasize_clicks: true,
+        };
+        let trim = TrimRange {
+            start_ms: 0,
+            end_ms: 1000,
+        };
+        let err = apply(Some(&zoom), &trim, &mut Vec::new(), &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("only X11 recordings"));
+    }
+}
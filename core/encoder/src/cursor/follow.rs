@@ -0,0 +1,64 @@
+//! Camera keyframes that follow the cursor.
+
+use super::Clip;
+use mandygif_protocol::{CameraKeyframe, CaptionRect, Easing};
+
+/// Spacing of generated camera keyframes.
+const STEP_MS: u64 = 500;
+/// Zoom back out after this long without movement or clicks.
+const IDLE_MS: u64 = 1500;
+
+impl Clip {
+    /// Keyframes that zoom in on the cursor while it is active, recentre
+    /// only when it nears the edge of the view, and zoom out when idle.
+    pub(super) fn follow(&self, w: f32) -> Vec<CameraKeyframe> {
+        let full = view((0.5, 0.5), 1.0);
+        let mut center = self.moves.first().map_or((0.5, 0.5), |m| m.1);
+        let mut raw = Vec::new();
+
+        for t in (0..=self.duration_ms).step_by(usize::try_from(STEP_MS).unwrap_or(1)) {
+            let recent =
+                |events: &[(u64, (f32, f32))]| events.iter().any(|e| e.0 <= t && e.0 + IDLE_MS > t);
+            let rect = if recent(&self.moves) || recent(&self.clicks) {
+                let pos = self
+                    .moves
+                    .iter()
+                    .rev()
+                    .find(|m| m.0 <= t)
+                    .map_or(center, |m| m.1);
+                if (pos.0 - center.0).abs() > w * 0.3 || (pos.1 - center.1).abs() > w * 0.3 {
+                    center = pos;
+                }
+                view(center, w)
+            } else {
+                full.clone()
+            };
+            raw.push((t, rect));
+        }
+
+        // Keep only the keyframes around changes so each move lasts one step
+        let mut keys = Vec::new();
+        for (i, (t, rect)) in raw.iter().enumerate() {
+            let changed = i == 0 || raw[i - 1].1 != *rect;
+            let changes_next = raw.get(i + 1).is_some_and(|n| n.1 != *rect);
+            if changed || changes_next {
+                keys.push(CameraKeyframe {
+                    at_ms: *t,
+                    rect: rect.clone(),
+                    easing: Easing::EaseInOut,
+                });
+            }
+        }
+        keys
+    }
+}
+
+/// Square-in-source-proportions view of width `w` centred on `c`.
+fn view(c: (f32, f32), w: f32) -> CaptionRect {
+    CaptionRect {
+        x: (c.0 - w / 2.0).clamp(0.0, 1.0 - w),
+        y: (c.1 - w / 2.0).clamp(0.0, 1.0 - w),
+        w,
+        h: w,
+    }
+}
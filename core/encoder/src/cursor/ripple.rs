@@ -0,0 +1,39 @@
+//! Rings drawn around clicks.
+
+#![allow(clippy::cast_precision_loss)]
+
+use super::Clip;
+use crate::camera::view_at;
+use mandygif_protocol::{Annotation, AnnotationShape, CameraKeyframe, CaptionRect};
+
+impl Clip {
+    /// Three expanding rings per click, positioned in the camera's view.
+    pub(super) fn ripples(&self, camera: &[CameraKeyframe]) -> Vec<Annotation> {
+        let mut out = Vec::new();
+        for &(t, pos) in &self.clicks {
+            let v = view_at(camera, t);
+            let (cx, cy) = ((pos.0 - v.x) / v.w, (pos.1 - v.y) / v.w);
+            if !(0.0..=1.0).contains(&cx) || !(0.0..=1.0).contains(&cy) {
+                continue;
+            }
+            for ring in 0..3u64 {
+                let r = 0.02 + 0.015 * ring as f32;
+                let ry = r * self.aspect;
+                out.push(Annotation {
+                    shape: AnnotationShape::Ellipse,
+                    rect: CaptionRect {
+                        x: cx - r,
+                        y: cy - ry,
+                        w: 2.0 * r,
+                        h: 2.0 * ry,
+                    },
+                    start_ms: t + ring * 80,
+                    end_ms: t + ring * 80 + 250,
+                    color: "#FFD60A".to_string(),
+                    stroke: 3,
+                });
+            }
+        }
+        out
+    }
+}
@@ -1,50 +1,134 @@
 #![allow(clippy::cast_precision_loss)]
 #![allow(clippy::uninlined_format_args)]
 
-use anyhow::{bail, Context, Result};
+use crate::annotate::{self, Canvas};
+use crate::camera;
+use crate::graph::FilterGraph;
+use crate::overlay;
+use crate::probe::probe_size;
+use anyhow::Result;
 use mandygif_captions::chain_filters_expr;
-use mandygif_protocol::Caption;
-use std::process::Command;
-use tracing::info;
+use mandygif_protocol::{Annotation, CameraKeyframe, Caption, Overlay, TrimRange};
+use std::path::Path;
 
-/// Check if ffmpeg is available.
-pub fn check_ffmpeg() -> Result<()> {
-    let output = Command::new("ffmpeg")
-        .arg("-version")
-        .output()
-        .context("ffmpeg not found - please install ffmpeg")?;
+/// Per-export edits applied on top of the recording.
+#[derive(Clone, Copy, Default)]
+pub struct Layers<'a> {
+    pub captions: &'a [Caption],
+    pub overlays: &'a [Overlay],
+    pub annotations: &'a [Annotation],
+    pub camera: &'a [CameraKeyframe],
+}
+
+/// What every export needs, whatever the format: the part of the
+/// recording to use, the output frame rate and width, and the layers drawn
+/// on top.
+#[derive(Clone, Copy)]
+pub struct EncodeOptions<'a> {
+    pub input: &'a Path,
+    pub trim: &'a TrimRange,
+    pub fps: u32,
+    /// Output width; the height follows the aspect ratio.
+    pub scale: Option<u32>,
+    pub layers: Layers<'a>,
+    pub out: &'a Path,
+}
 
-    if !output.status.success() {
-        bail!("ffmpeg exists but returned error");
+impl EncodeOptions<'_> {
+    /// `-ss`/`-t` arguments selecting the trimmed clip of the input.
+    #[must_use]
+    pub fn trim_args(&self) -> [String; 4] {
+        let start = ms_to_sec(self.trim.start_ms);
+        let dur = ms_to_sec(self.trim.end_ms.saturating_sub(self.trim.start_ms));
+        ["-ss".into(), start, "-t".into(), dur]
     }
 
-    let version = String::from_utf8_lossy(&output.stdout);
-    let first_line = version.lines().next().unwrap_or("unknown");
-    info!("Using {}", first_line);
-    Ok(())
+    /// `build_filter` for these options, with sprites written to `workdir`.
+    ///
+    /// # Errors
+    /// Returns error if probing the input or generating any layer fails.
+    pub fn filter(&self, layers: &Layers, workdir: &Path) -> Result<String> {
+        build_filter(self.input, self.fps, self.scale, layers, workdir)
+    }
 }
 
-/// Build ffmpeg video filter string (fps, scale, captions).
+/// Build ffmpeg video filter string (fps, camera, scale, annotations,
+/// overlays, captions).
+///
+/// The result has a single unlabelled input and output, so it works both
+/// as `-vf` and as the head of a `-lavfi` graph. Annotation sprites are
+/// written to `workdir`, which must outlive the ffmpeg run.
 ///
 /// # Errors
-/// Returns error if caption filter generation fails.
-pub fn build_filter(fps: u32, scale: Option<u32>, caps: &[Caption]) -> Result<String> {
-    let mut filters = vec![format!("fps={}", fps)];
+/// Returns error if probing the input or generating any layer fails.
+pub fn build_filter(
+    input: &Path,
+    fps: u32,
+    scale: Option<u32>,
+    layers: &Layers,
+    workdir: &Path,
+) -> Result<String> {
+    let mut g = FilterGraph::default();
+    g.push(format!("fps={}", fps));
+
+    // Only pixel-exact layers need to know the source size
+    let src = if layers.camera.is_empty() && layers.annotations.is_empty() {
+        None
+    } else {
+        Some(probe_size(input)?)
+    };
+
+    // Camera moves crop the full-resolution source before it is scaled down
+    if let Some(size) = src.filter(|_| !layers.camera.is_empty()) {
+        g.push(camera::zoompan(layers.camera, output_size(size, None), fps));
+    }
 
     // FIX: Use -2 instead of -1 to ensure height is divisible by 2 (required for MP4/H.264)
     if let Some(width) = scale {
-        filters.push(format!("scale={width}:-2:flags=lanczos"));
+        g.push(format!("scale={width}:-2:flags=lanczos"));
     } else {
         // If no scaling is requested, ensure input dimensions are even
-        filters.push("scale=trunc(iw/2)*2:trunc(ih/2)*2".to_string());
+        g.push("scale=trunc(iw/2)*2:trunc(ih/2)*2");
+    }
+
+    // Annotations come first so redaction never touches logos or captions
+    if let Some(size) = src.filter(|_| !layers.annotations.is_empty()) {
+        let canvas = Canvas {
+            size: output_size(size, scale),
+            fps,
+            workdir,
+        };
+        for ann in layers.annotations {
+            annotate::draw(&mut g, ann, &canvas)?;
+        }
+    }
+
+    // Overlays are composited after scaling so their rects match the output frame
+    for ov in layers.overlays {
+        overlay::composite(&mut g, ov, fps)?;
     }
 
     // Add caption filters after scaling
-    if !caps.is_empty() {
-        filters.push(chain_filters_expr(caps)?);
+    if !layers.captions.is_empty() {
+        g.push(chain_filters_expr(layers.captions)?);
     }
 
-    Ok(filters.join(","))
+    Ok(g.finish())
+}
+
+/// Frame size produced by the scale step of `build_filter`.
+#[must_use]
+pub fn output_size(src: (u32, u32), scale: Option<u32>) -> (u32, u32) {
+    let (iw, ih) = (u64::from(src.0), u64::from(src.1));
+    match scale {
+        // Mirrors ffmpeg's `-2`: av_rescale rounds to nearest, then to a multiple of 2
+        Some(w) if iw > 0 => {
+            let h = (u64::from(w) * ih + iw) / (2 * iw) * 2;
+            (w, u32::try_from(h).unwrap_or(u32::MAX))
+        }
+        Some(w) => (w, 0),
+        None => (src.0 / 2 * 2, src.1 / 2 * 2),
+    }
 }
 
 /// Convert milliseconds to seconds string.
@@ -52,3 +136,54 @@ pub fn build_filter(fps: u32, scale: Option<u32>, caps: &[Caption]) -> Result<St
 pub fn ms_to_sec(ms: u64) -> String {
     format!("{:.3}", (ms as f64) / 1000.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::CaptionRect;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_output_size_matches_ffmpeg_rounding() {
+        assert_eq!(output_size((1920, 1080), Some(480)), (480, 270));
+        assert_eq!(output_size((1000, 333), Some(480)), (480, 160));
+        assert_eq!(output_size((641, 481), None), (640, 480));
+    }
+
+    #[test]
+    fn test_overlay_graph_keeps_single_io() -> Result<()> {
+        let overlays = [Overlay {
+            source: PathBuf::from("/tmp/logo.png"),
+            rect: CaptionRect {
+                x: 0.8,
+                y: 0.8,
+                w: 0.1,
+                h: 0.1,
+            },
+            opacity: 0.5,
+            start_ms: 0,
+            end_ms: 2000,
+            fade_ms: 0,
+        }];
+        let layers = Layers {
+            overlays: &overlays,
+            ..Layers::default()
+        };
+        let filter = build_filter(
+            Path::new("in.mp4"),
+            15,
+            Some(480),
+            &layers,
+            Path::new("/tmp"),
+        )?;
+
+        assert!(filter.starts_with("fps=15,scale=480:-2:flags=lanczos[v1];movie='/tmp/logo.png'"));
+        assert!(filter.contains(
+            "[v1]scale2ref=w=main_w*0.100000:h=main_h*0.100000:force_original_aspect_ratio=decrease"
+        ));
+        // The final chain must stay unlabelled so `-vf` and `[x]` suffixing both work
+        let last = filter.rsplit(';').next().unwrap_or_default();
+        assert!(last.contains("overlay=") && !last.ends_with(']'));
+        Ok(())
+    }
+}
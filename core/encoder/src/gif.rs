@@ -1,35 +1,28 @@
 #![allow(clippy::uninlined_format_args)]
 
-use crate::ffmpeg::{build_filter, ms_to_sec};
+use crate::ffmpeg::EncodeOptions;
 use anyhow::{bail, Context, Result};
-use mandygif_protocol::{Caption, LoopMode, TrimRange};
-use std::path::Path;
+use mandygif_protocol::{FrameRateMode, LoopMode};
 use std::process::{Command, Stdio};
-use tracing::{debug, error, warn};
+use tracing::{debug, error};
 
 /// Encode GIF using ffmpeg palettegen.
 pub fn encode_gif(
-    input: &Path,
-    trim: &TrimRange,
-    fps: u32,
-    scale: Option<u32>,
+    opts: &EncodeOptions,
     loop_mode: &LoopMode,
-    caps: &[Caption],
-    out: &Path,
+    frame_rate: FrameRateMode,
 ) -> Result<()> {
     let temp = tempfile::tempdir().context("Failed to create temp dir")?;
     let palette = temp.path().join("palette.png");
 
-    let filter = build_filter(fps, scale, caps)?;
-    let start = ms_to_sec(trim.start_ms);
-    let dur = ms_to_sec(trim.end_ms.saturating_sub(trim.start_ms));
+    let filter = opts.filter(&opts.layers, temp.path())?;
 
     // Step 1: Generate palette
     debug!("Generating palette for GIF");
     let output = Command::new("ffmpeg")
-        .args(["-ss", &start, "-t", &dur])
+        .args(opts.trim_args())
         .arg("-i")
-        .arg(input)
+        .arg(opts.input)
         .arg("-vf")
         .arg(format!("{filter},palettegen"))
         .arg("-y")
@@ -48,13 +41,26 @@ pub fn encode_gif(
     // Step 2: Generate GIF
     debug!("Encoding GIF with palette");
     let mut cmd = Command::new("ffmpeg");
-    cmd.args(["-ss", &start, "-t", &dur])
+    cmd.args(opts.trim_args())
         .arg("-i")
-        .arg(input)
+        .arg(opts.input)
         .arg("-i")
         .arg(&palette)
-        .arg("-lavfi")
-        .arg(format!("{filter} [x]; [x][1:v] paletteuse"));
+        .arg("-lavfi");
+    let filter = match pingpong(loop_mode) {
+        Some(pingpong) => format!("{filter},{pingpong}"),
+        None => filter,
+    };
+    match decimate(frame_rate, opts.fps) {
+        Some(decimate) => {
+            cmd.arg(format!("{filter},{decimate} [x]; [x][1:v] paletteuse"))
+                .args(["-vsync", "vfr"]);
+        }
+        None => {
+            cmd.arg(format!("{filter} [x]; [x][1:v] paletteuse"));
+        }
+    }
+    cmd.arg("-an");
 
     match loop_mode {
         LoopMode::Once => {
@@ -65,13 +71,9 @@ pub fn encode_gif(
         }
     }
 
-    if matches!(loop_mode, LoopMode::Pingpong) {
-        warn!("Ping-pong loop mode not yet implemented for GIF");
-    }
-
     let output = cmd
         .arg("-y")
-        .arg(out)
+        .arg(opts.out)
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
         .output()
@@ -85,3 +87,51 @@ pub fn encode_gif(
 
     Ok(())
 }
+
+/// Filters that play the clip forwards and then backwards, without
+/// showing the last frame twice at the turn.
+///
+/// `reverse` holds every frame in memory, which GIF-sized clips afford.
+fn pingpong(loop_mode: &LoopMode) -> Option<&'static str> {
+    matches!(loop_mode, LoopMode::Pingpong).then_some(
+        "split [fwd][rev]; [rev] reverse,trim=start_frame=1 [back]; [fwd][back] concat=n=2:v=1:a=0",
+    )
+}
+
+/// Filter that drops exact repeats of the previous frame at a variable
+/// frame rate, so the frame before a run is shown for the whole run. At
+/// most `max_gap_ms` worth of frames are dropped in a row.
+fn decimate(frame_rate: FrameRateMode, fps: u32) -> Option<String> {
+    let FrameRateMode::Variable { max_gap_ms } = frame_rate else {
+        return None;
+    };
+    let max = (u64::from(max_gap_ms) * u64::from(fps) / 1000).max(1);
+    Some(format!("mpdecimate=hi=0:lo=0:frac=0:max={max}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimate_only_at_variable_rate() {
+        assert_eq!(decimate(FrameRateMode::Constant, 15), None);
+        assert_eq!(
+            decimate(FrameRateMode::Variable { max_gap_ms: 1000 }, 15).as_deref(),
+            Some("mpdecimate=hi=0:lo=0:frac=0:max=15")
+        );
+    }
+
+    #[test]
+    fn test_pingpong_appends_reversed_frames() {
+        assert_eq!(pingpong(&LoopMode::Normal), None);
+        assert_eq!(pingpong(&LoopMode::Once), None);
+
+        let filter = format!("fps=15,{}", pingpong(&LoopMode::Pingpong).unwrap());
+        assert_eq!(
+            filter,
+            "fps=15,split [fwd][rev]; [rev] reverse,trim=start_frame=1 [back]; \
+             [fwd][back] concat=n=2:v=1:a=0"
+        );
+    }
+}
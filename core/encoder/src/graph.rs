@@ -0,0 +1,45 @@
+/// Incrementally built ffmpeg filtergraph.
+///
+/// The main chain keeps a single unlabelled input and output, so the result
+/// works both as `-vf` and as the head of a `-lavfi` graph. Compositing
+/// steps cut the main chain at a label, add side chains, and resume the main
+/// chain with a filter consuming those labels.
+#[derive(Default)]
+pub struct FilterGraph {
+    segments: Vec<String>,
+    chain: Vec<String>,
+    labels: usize,
+}
+
+impl FilterGraph {
+    /// Append a filter to the main chain.
+    pub fn push(&mut self, filter: impl Into<String>) {
+        self.chain.push(filter.into());
+    }
+
+    /// Terminate the main chain at a fresh label and return it. The next
+    /// `push` must start with a filter that consumes the label.
+    pub fn cut(&mut self) -> String {
+        let label = self.label("v");
+        self.segments
+            .push(format!("{}[{label}]", self.chain.join(",")));
+        self.chain.clear();
+        label
+    }
+
+    /// Add a complete side chain (including its own input/output labels).
+    pub fn side(&mut self, chain: String) {
+        self.segments.push(chain);
+    }
+
+    /// Allocate a unique pad label.
+    pub fn label(&mut self, prefix: &str) -> String {
+        self.labels += 1;
+        format!("{prefix}{}", self.labels)
+    }
+
+    pub fn finish(mut self) -> String {
+        self.segments.push(self.chain.join(","));
+        self.segments.join(";")
+    }
+}
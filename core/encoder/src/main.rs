@@ -2,11 +2,20 @@
 
 #![allow(clippy::wildcard_imports)]
 
+mod annotate;
+mod audio;
+mod camera;
+mod cursor;
 mod ffmpeg;
 mod gif;
+mod graph;
+mod overlay;
+mod probe;
+mod sprite;
 mod video;
 
 use anyhow::{Context, Result};
+use ffmpeg::{EncodeOptions, Layers};
 use mandygif_protocol::*;
 use std::io::{self, BufRead, Write};
 use tracing::{error, info};
@@ -19,7 +28,7 @@ fn main() -> Result<()> {
         .init();
 
     info!("encoder starting (protocol v{})", PROTOCOL_VERSION);
-    ffmpeg::check_ffmpeg()?;
+    probe::check_ffmpeg()?;
 
     let stdin = io::stdin();
     let mut stdout = io::stdout();
@@ -43,10 +52,30 @@ fn handle_command(line: &str, out: &mut io::Stdout) -> Result<()> {
             fps,
             scale_px,
             loop_mode,
+            frame_rate,
             captions,
+            overlays,
+            mut annotations,
+            mut camera,
+            cursor_zoom,
             out: path,
         } => {
-            gif::encode_gif(&input, &trim, fps, scale_px, &loop_mode, &captions, &path)?;
+            cursor::apply(cursor_zoom.as_ref(), &trim, &mut camera, &mut annotations)?;
+            let layers = Layers {
+                captions: &captions,
+                overlays: &overlays,
+                annotations: &annotations,
+                camera: &camera,
+            };
+            let opts = EncodeOptions {
+                input: &input,
+                trim: &trim,
+                fps,
+                scale: scale_px,
+                layers,
+                out: &path,
+            };
+            gif::encode_gif(&opts, &loop_mode, frame_rate)?;
             send_done(out, path)?;
         }
         EncoderCommand::Mp4 {
@@ -56,9 +85,30 @@ fn handle_command(line: &str, out: &mut io::Stdout) -> Result<()> {
             scale_px,
             quality,
             captions,
+            subtitles,
+            audio,
+            overlays,
+            mut annotations,
+            mut camera,
+            cursor_zoom,
             out: path,
         } => {
-            video::encode_mp4(&input, &trim, fps, scale_px, quality, &captions, &path)?;
+            cursor::apply(cursor_zoom.as_ref(), &trim, &mut camera, &mut annotations)?;
+            let layers = Layers {
+                captions: &captions,
+                overlays: &overlays,
+                annotations: &annotations,
+                camera: &camera,
+            };
+            let opts = EncodeOptions {
+                input: &input,
+                trim: &trim,
+                fps,
+                scale: scale_px,
+                layers,
+                out: &path,
+            };
+            video::encode_mp4(&opts, quality, subtitles, audio)?;
             send_done(out, path)?;
         }
         EncoderCommand::Webp {
@@ -69,11 +119,28 @@ fn handle_command(line: &str, out: &mut io::Stdout) -> Result<()> {
             quality,
             lossless,
             captions,
+            overlays,
+            mut annotations,
+            mut camera,
+            cursor_zoom,
             out: path,
         } => {
-            video::encode_webp(
-                &input, &trim, fps, scale_px, quality, lossless, &captions, &path,
-            )?;
+            cursor::apply(cursor_zoom.as_ref(), &trim, &mut camera, &mut annotations)?;
+            let layers = Layers {
+                captions: &captions,
+                overlays: &overlays,
+                annotations: &annotations,
+                camera: &camera,
+            };
+            let opts = EncodeOptions {
+                input: &input,
+                trim: &trim,
+                fps,
+                scale: scale_px,
+                layers,
+                out: &path,
+            };
+            video::encode_webp(&opts, quality, lossless)?;
             send_done(out, path)?;
         }
     }
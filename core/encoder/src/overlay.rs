@@ -0,0 +1,88 @@
+#![allow(clippy::cast_precision_loss)]
+
+use crate::graph::FilterGraph;
+use anyhow::{bail, Result};
+use mandygif_protocol::Overlay;
+use std::path::Path;
+
+/// Composite an image overlay onto the main chain.
+///
+/// Images are loaded with the `movie` source, so SVG support depends on
+/// ffmpeg being built with librsvg.
+pub fn composite(g: &mut FilterGraph, ov: &Overlay, fps: u32) -> Result<()> {
+    check_source(&ov.source)?;
+
+    let start_s = ov.start_ms as f64 / 1000.0;
+    let end_s = ov.end_ms as f64 / 1000.0;
+
+    let mut src = vec![
+        still_source(&ov.source, fps)?,
+        "format=rgba".to_string(),
+        format!("colorchannelmixer=aa={:.3}", ov.opacity.clamp(0.0, 1.0)),
+    ];
+    if ov.fade_ms > 0 {
+        let fade_s = ov.fade_ms as f64 / 1000.0;
+        src.push(format!("fade=t=in:st={start_s:.3}:d={fade_s:.3}:alpha=1"));
+        src.push(format!(
+            "fade=t=out:st={:.3}:d={fade_s:.3}:alpha=1",
+            (end_s - fade_s).max(start_s)
+        ));
+    }
+
+    let base = g.cut();
+    let raw = g.label("ovsrc");
+    let img = g.label("ov");
+    let reference = g.label("ref");
+    g.side(format!("{}[{raw}]", src.join(",")));
+    let r = &ov.rect;
+    if r.h > 0.0 {
+        // Fit inside the box and centre there, whatever the image's shape
+        g.side(format!(
+            "[{raw}][{base}]scale2ref=w=main_w*{:.6}:h=main_h*{:.6}:force_original_aspect_ratio=decrease[{img}][{reference}]",
+            r.w, r.h
+        ));
+        g.push(format!(
+            "[{reference}][{img}]overlay=x=main_w*{:.6}+(main_w*{:.6}-overlay_w)/2:y=main_h*{:.6}+(main_h*{:.6}-overlay_h)/2:shortest=1:enable='between(t,{start_s:.3},{end_s:.3})'",
+            r.x, r.w, r.y, r.h,
+        ));
+    } else {
+        g.side(format!(
+            "[{raw}][{base}]scale2ref=w=main_w*{:.6}:h=ow/a[{img}][{reference}]",
+            r.w
+        ));
+        g.push(format!(
+            "[{reference}][{img}]overlay=x=main_w*{:.6}:y=main_h*{:.6}:shortest=1:enable='between(t,{start_s:.3},{end_s:.3})'",
+            r.x, r.y,
+        ));
+    }
+    Ok(())
+}
+
+/// A still image repeated as an endless stream at the output rate, so it
+/// can be faded and overlaid like video.
+pub fn still_source(path: &Path, fps: u32) -> Result<String> {
+    Ok(format!(
+        "movie={}:loop=0,setpts=N/({fps}*TB)",
+        ff_path(path)?
+    ))
+}
+
+fn check_source(path: &Path) -> Result<()> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("png" | "svg") => Ok(()),
+        _ => bail!("Overlay must be a PNG or SVG image: {}", path.display()),
+    }
+}
+
+/// Quote a path for use as a filter option value.
+fn ff_path(path: &Path) -> Result<String> {
+    let s = path.to_string_lossy();
+    if s.contains('\'') {
+        bail!("Overlay path may not contain quotes: {s}");
+    }
+    Ok(format!("'{}'", s.replace('\\', "\\\\").replace(':', "\\:")))
+}
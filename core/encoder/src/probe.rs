@@ -0,0 +1,50 @@
+//! The ffmpeg tools and what they report about a recording.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+use tracing::info;
+
+/// Check if ffmpeg is available.
+pub fn check_ffmpeg() -> Result<()> {
+    let output = Command::new("ffmpeg")
+        .arg("-version")
+        .output()
+        .context("ffmpeg not found - please install ffmpeg")?;
+
+    if !output.status.success() {
+        bail!("ffmpeg exists but returned error");
+    }
+
+    let version = String::from_utf8_lossy(&output.stdout);
+    let first_line = version.lines().next().unwrap_or("unknown");
+    info!("Using {}", first_line);
+    Ok(())
+}
+
+/// Probe the dimensions of the first video stream.
+///
+/// # Errors
+/// Returns error if ffprobe is missing or reports no video stream.
+pub fn probe_size(input: &Path) -> Result<(u32, u32)> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=width,height", "-of", "csv=p=0:s=x"])
+        .arg(input)
+        .output()
+        .context("ffprobe not found - please install ffmpeg")?;
+
+    if !output.status.success() {
+        bail!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let (w, h) = text
+        .trim()
+        .split_once('x')
+        .context("ffprobe reported no video stream")?;
+    Ok((w.parse()?, h.parse()?))
+}
@@ -0,0 +1,228 @@
+//! CPU rasterizer for annotation shapes ffmpeg has no filter for.
+//!
+//! Shapes are drawn anti-aliased into a transparent frame-sized PNG that is
+//! then overlaid at the origin.
+
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_sign_loss)]
+
+mod distance;
+
+use anyhow::{Context, Result};
+use distance::{segment_distance, stroke_coverage, triangle_distance};
+use image::{Rgba, RgbaImage};
+use mandygif_protocol::{Annotation, AnnotationShape};
+use std::path::Path;
+
+type Point = (f32, f32);
+
+/// Render an arrow or ellipse annotation for a `size` frame into `path`.
+pub fn render(ann: &Annotation, size: (u32, u32), color: [u8; 4], path: &Path) -> Result<()> {
+    let (fw, fh) = (size.0 as f32, size.1 as f32);
+    let from = (ann.rect.x * fw, ann.rect.y * fh);
+    let to = (
+        (ann.rect.x + ann.rect.w) * fw,
+        (ann.rect.y + ann.rect.h) * fh,
+    );
+    let half = (ann.stroke.max(1) as f32) / 2.0;
+
+    let shape = match ann.shape {
+        AnnotationShape::Arrow => Shape::arrow(from, to, half),
+        _ => Shape::ellipse(from, to, half),
+    };
+
+    let mut img = RgbaImage::new(size.0, size.1);
+    let (x0, y0, x1, y1) = shape.bounds(size);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let cov = shape.coverage((x as f32 + 0.5, y as f32 + 0.5));
+            if cov > 0.0 {
+                let [r, g, b, a] = color;
+                let alpha = (f32::from(a) * cov).round() as u8;
+                img.put_pixel(x, y, Rgba([r, g, b, alpha]));
+            }
+        }
+    }
+
+    img.save(path)
+        .with_context(|| format!("Failed to write sprite {}", path.display()))
+}
+
+enum Shape {
+    Ellipse {
+        center: Point,
+        radii: Point,
+        half: f32,
+    },
+    Arrow {
+        tail: Point,
+        base: Point,
+        head: [Point; 3],
+        half: f32,
+    },
+}
+
+impl Shape {
+    fn ellipse(a: Point, b: Point, half: f32) -> Self {
+        // Inset so the stroke stays inside the rect.
+        Self::Ellipse {
+            center: ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0),
+            radii: (
+                ((a.0 - b.0).abs() / 2.0 - half).max(1.0),
+                ((a.1 - b.1).abs() / 2.0 - half).max(1.0),
+            ),
+            half,
+        }
+    }
+
+    fn arrow(tail: Point, tip: Point, half: f32) -> Self {
+        let (dx, dy) = (tip.0 - tail.0, tip.1 - tail.1);
+        let len = dx.hypot(dy).max(1.0);
+        let (ux, uy) = (dx / len, dy / len);
+        let head_len = (half * 8.0).max(12.0).min(len * 0.6);
+        let head_half = head_len * 0.6;
+        let base = (tip.0 - ux * head_len, tip.1 - uy * head_len);
+        Self::Arrow {
+            tail,
+            base,
+            head: [
+                tip,
+                (base.0 - uy * head_half, base.1 + ux * head_half),
+                (base.0 + uy * head_half, base.1 - ux * head_half),
+            ],
+            half,
+        }
+    }
+
+    /// Pixel bounding box (exclusive end), clamped to the frame.
+    fn bounds(&self, size: (u32, u32)) -> (u32, u32, u32, u32) {
+        let pts: Vec<Point> = match self {
+            Self::Ellipse {
+                center,
+                radii,
+                half,
+            } => vec![
+                (center.0 - radii.0 - half, center.1 - radii.1 - half),
+                (center.0 + radii.0 + half, center.1 + radii.1 + half),
+            ],
+            Self::Arrow {
+                tail, head, half, ..
+            } => {
+                let mut pts = vec![
+                    (tail.0 - half, tail.1 - half),
+                    (tail.0 + half, tail.1 + half),
+                ];
+                pts.extend_from_slice(head);
+                pts
+            }
+        };
+        let clamp = |v: f32, max: u32| (v.max(0.0) as u32).min(max);
+        let min_x = pts.iter().map(|p| p.0).fold(f32::MAX, f32::min) - 1.0;
+        let min_y = pts.iter().map(|p| p.1).fold(f32::MAX, f32::min) - 1.0;
+        let max_x = pts.iter().map(|p| p.0).fold(f32::MIN, f32::max) + 2.0;
+        let max_y = pts.iter().map(|p| p.1).fold(f32::MIN, f32::max) + 2.0;
+        (
+            clamp(min_x, size.0),
+            clamp(min_y, size.1),
+            clamp(max_x, size.0),
+            clamp(max_y, size.1),
+        )
+    }
+
+    /// Anti-aliased coverage of the pixel centred at `p`, in `0..=1`.
+    fn coverage(&self, p: Point) -> f32 {
+        match self {
+            Self::Ellipse {
+                center,
+                radii,
+                half,
+            } => {
+                let (dx, dy) = (p.0 - center.0, p.1 - center.1);
+                let (rx2, ry2) = (radii.0 * radii.0, radii.1 * radii.1);
+                let f = dx * dx / rx2 + dy * dy / ry2 - 1.0;
+                let grad = 2.0 * (dx * dx / (rx2 * rx2) + dy * dy / (ry2 * ry2)).sqrt();
+                if grad == 0.0 {
+                    return 0.0;
+                }
+                stroke_coverage(f.abs() / grad, *half)
+            }
+            Self::Arrow {
+                tail,
+                base,
+                head,
+                half,
+            } => {
+                let shaft = stroke_coverage(segment_distance(p, *tail, *base), *half);
+                let tip = (0.5 - triangle_distance(p, head)).clamp(0.0, 1.0);
+                shaft.max(tip)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::CaptionRect;
+
+    fn annotation(shape: AnnotationShape, rect: CaptionRect) -> Annotation {
+        Annotation {
+            shape,
+            rect,
+            start_ms: 0,
+            end_ms: 1000,
+            color: "#FF0000".to_string(),
+            stroke: 4,
+        }
+    }
+
+    #[test]
+    fn test_ellipse_is_hollow() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("ellipse.png");
+        let rect = CaptionRect {
+            x: 0.25,
+            y: 0.25,
+            w: 0.5,
+            h: 0.5,
+        };
+        render(
+            &annotation(AnnotationShape::Ellipse, rect),
+            (200, 100),
+            [255, 0, 0, 255],
+            &path,
+        )?;
+
+        let img = image::open(&path)?.to_rgba8();
+        assert_eq!(img.get_pixel(100, 50)[3], 0, "centre must stay clear");
+        assert_eq!(img.get_pixel(52, 50)[3], 255, "left edge is stroked");
+        assert_eq!(img.get_pixel(10, 10)[3], 0, "outside stays clear");
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_reaches_tip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("arrow.png");
+        // Right-to-left arrow: negative width
+        let rect = CaptionRect {
+            x: 0.9,
+            y: 0.5,
+            w: -0.8,
+            h: 0.0,
+        };
+        render(
+            &annotation(AnnotationShape::Arrow, rect),
+            (100, 100),
+            [255, 0, 0, 255],
+            &path,
+        )?;
+
+        let img = image::open(&path)?.to_rgba8();
+        assert_eq!(img.get_pixel(12, 50)[3], 255, "head near the tip");
+        assert_eq!(img.get_pixel(80, 50)[3], 255, "shaft");
+        assert_eq!(img.get_pixel(80, 60)[3], 0, "beside the shaft");
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! Distances from a pixel to the outlines shapes are drawn from.
+
+use super::Point;
+
+pub(super) fn stroke_coverage(dist: f32, half: f32) -> f32 {
+    (half + 0.5 - dist).clamp(0.0, 1.0)
+}
+
+pub(super) fn segment_distance(p: Point, a: Point, b: Point) -> f32 {
+    let (abx, aby) = (b.0 - a.0, b.1 - a.1);
+    let len2 = abx * abx + aby * aby;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * abx + (p.1 - a.1) * aby) / len2).clamp(0.0, 1.0)
+    };
+    (p.0 - (a.0 + abx * t)).hypot(p.1 - (a.1 + aby * t))
+}
+
+/// Signed distance approximation to a triangle (negative inside).
+pub(super) fn triangle_distance(p: Point, tri: &[Point; 3]) -> f32 {
+    let centroid = (
+        (tri[0].0 + tri[1].0 + tri[2].0) / 3.0,
+        (tri[0].1 + tri[1].1 + tri[2].1) / 3.0,
+    );
+    let mut dist = f32::MIN;
+    for i in 0..3 {
+        let (a, b) = (tri[i], tri[(i + 1) % 3]);
+        let (ex, ey) = (b.0 - a.0, b.1 - a.1);
+        let len = ex.hypot(ey);
+        if len == 0.0 {
+            continue;
+        }
+        let side = |q: Point| (ex * (q.1 - a.1) - ey * (q.0 - a.0)) / len;
+        // Orient each edge so the centroid lies on the negative side.
+        let sign = if side(centroid) > 0.0 { -1.0 } else { 1.0 };
+        dist = dist.max(sign * side(p));
+    }
+    dist
+}
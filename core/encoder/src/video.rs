@@ -1,35 +1,65 @@
 #![allow(clippy::cast_possible_truncation)]
 #![allow(clippy::cast_sign_loss)]
 
-use crate::ffmpeg::{build_filter, ms_to_sec};
+use crate::audio;
+use crate::ffmpeg::{EncodeOptions, Layers};
 use anyhow::{bail, Context, Result};
-use mandygif_protocol::{Caption, TrimRange};
-use std::path::Path;
+use mandygif_captions::to_srt;
+use mandygif_protocol::{Caption, ExportAudio, SubtitleMode};
+use std::fs;
 use std::process::{Command, Stdio};
 use tracing::{debug, error};
 
-/// Encode MP4 using ffmpeg.
+/// Encode MP4 using ffmpeg. Recorded audio tracks are trimmed with the
+/// video and mixed into a single AAC track.
 pub fn encode_mp4(
-    input: &Path,
-    trim: &TrimRange,
-    fps: u32,
-    scale: Option<u32>,
+    opts: &EncodeOptions,
     qual: f32,
-    caps: &[Caption],
-    out: &Path,
+    subs: SubtitleMode,
+    sound: ExportAudio,
 ) -> Result<()> {
     let crf = (51.0 - (qual * 33.0)).round() as u32;
-    let start = ms_to_sec(trim.start_ms);
-    let dur = ms_to_sec(trim.end_ms.saturating_sub(trim.start_ms));
-    let filter = build_filter(fps, scale, caps)?;
+    let layers = &opts.layers;
 
-    debug!("Encoding MP4 (CRF {crf})");
+    // Soft subtitles travel as their own track, so keep them out of the frames.
+    let soft = subs == SubtitleMode::Soft && !layers.captions.is_empty();
+    let burned = if soft {
+        Layers {
+            captions: &[],
+            ..*layers
+        }
+    } else {
+        *layers
+    };
+    let temp = tempfile::tempdir().context("Failed to create temp dir")?;
+    let filter = opts.filter(&burned, temp.path())?;
+    let tracks = if sound == ExportAudio::Mute {
+        0
+    } else {
+        audio::probe_tracks(opts.input)?
+    };
+
+    debug!("Encoding MP4 (CRF {crf}, {tracks} audio tracks)");
+
+    let mut cmd = Command::new("ffmpeg");
+    cmd.args(opts.trim_args()).arg("-i").arg(opts.input);
+
+    let srt = temp.path().join("captions.srt");
+    if soft {
+        let clip_ms = opts.trim.end_ms.saturating_sub(opts.trim.start_ms);
+        fs::write(&srt, to_srt(&within_clip(layers.captions, clip_ms)))
+            .context("Failed to write subtitle track")?;
+        cmd.arg("-i").arg(&srt);
+    }
+
+    cmd.args(["-map", "0:v:0"])
+        .args(audio::mp4_args(tracks, sound));
+    if soft {
+        cmd.args(["-map", "1:s", "-c:s", "mov_text"]);
+    }
 
     // FIX: Use output() to capture stderr for debugging
-    let output = Command::new("ffmpeg")
-        .args(["-ss", &start, "-t", &dur])
-        .arg("-i")
-        .arg(input)
+    let output = cmd
         .arg("-vf")
         .arg(filter)
         .args(["-c:v", "libx264", "-preset", "medium"])
@@ -37,7 +67,7 @@ pub fn encode_mp4(
         .arg(crf.to_string())
         .args(["-pix_fmt", "yuv420p", "-movflags", "+faststart"])
         .arg("-y")
-        .arg(out)
+        .arg(opts.out)
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
         .output()
@@ -52,28 +82,32 @@ pub fn encode_mp4(
     Ok(())
 }
 
+/// `captions` cut to the first `clip_ms` of the output. ffmpeg keeps a
+/// subtitle track running to its last cue, so one past the trim would
+/// otherwise lengthen the MP4.
+fn within_clip(captions: &[Caption], clip_ms: u64) -> Vec<Caption> {
+    captions
+        .iter()
+        .filter(|c| c.start_ms < clip_ms)
+        .map(|c| Caption {
+            end_ms: c.end_ms.min(clip_ms),
+            ..c.clone()
+        })
+        .collect()
+}
+
 /// Encode WebP using ffmpeg.
-#[allow(clippy::too_many_arguments)]
-pub fn encode_webp(
-    input: &Path,
-    trim: &TrimRange,
-    fps: u32,
-    scale: Option<u32>,
-    qual: f32,
-    lossless: bool,
-    caps: &[Caption],
-    out: &Path,
-) -> Result<()> {
-    let start = ms_to_sec(trim.start_ms);
-    let dur = ms_to_sec(trim.end_ms.saturating_sub(trim.start_ms));
-    let filter = build_filter(fps, scale, caps)?;
+pub fn encode_webp(opts: &EncodeOptions, qual: f32, lossless: bool) -> Result<()> {
+    let temp = tempfile::tempdir().context("Failed to create temp dir")?;
+    let filter = opts.filter(&opts.layers, temp.path())?;
 
     let mut cmd = Command::new("ffmpeg");
-    cmd.args(["-ss", &start, "-t", &dur])
+    cmd.args(opts.trim_args())
         .arg("-i")
-        .arg(input)
+        .arg(opts.input)
         .arg("-vf")
-        .arg(filter);
+        .arg(filter)
+        .arg("-an");
 
     if lossless {
         cmd.args(["-lossless", "1"]);
@@ -86,7 +120,7 @@ pub fn encode_webp(
     // FIX: Use output() to capture stderr
     let output = cmd
         .args(["-loop", "0", "-y"])
-        .arg(out)
+        .arg(opts.out)
         .stdout(Stdio::null())
         .stderr(Stdio::piped())
         .output()
@@ -100,3 +134,22 @@ pub fn encode_webp(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_captions::{parse_srt, CaptionTemplate};
+
+    #[test]
+    fn test_captions_clamped_to_clip() -> Result<()> {
+        let srt = "1\n00:00:01,000 --> 00:00:02,000\nInside\n\n\
+                   2\n00:00:04,000 --> 00:00:09,000\nStraddles\n\n\
+                   3\n00:00:06,000 --> 00:00:07,000\nAfter\n";
+        let caps = parse_srt(srt, &CaptionTemplate::default())?;
+        let clipped = within_clip(&caps, 5000);
+        assert_eq!(clipped.len(), 2);
+        assert_eq!(clipped[0], caps[0]);
+        assert_eq!((clipped[1].start_ms, clipped[1].end_ms), (4000, 5000));
+        Ok(())
+    }
+}
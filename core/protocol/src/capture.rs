@@ -0,0 +1,133 @@
+//! What and how a recorder captures.
+
+use crate::types::CaptureRegion;
+use serde::{Deserialize, Serialize};
+
+/// What a recording captures.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureTarget {
+    /// The `region` of the start command.
+    #[default]
+    Region,
+    /// A whole monitor.
+    Monitor { monitor: MonitorId },
+    /// A top-level X11 window, followed as it moves.
+    Window { xid: u64 },
+}
+
+/// A monitor by position in the `Targets` list or by output name.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MonitorId {
+    Index(u32),
+    /// Output name such as `HDMI-1`.
+    Name(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MonitorInfo {
+    pub index: u32,
+    pub name: String,
+    pub region: CaptureRegion,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowInfo {
+    pub xid: u64,
+    pub title: String,
+    /// Where the window is now, frame excluded.
+    pub region: CaptureRegion,
+}
+
+/// Which capture implementation a recorder should use.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureBackendKind {
+    /// Wayland when `WAYLAND_DISPLAY` is set, otherwise X11.
+    #[default]
+    Auto,
+    X11,
+    /// xdg-desktop-portal screen cast over PipeWire.
+    Wayland,
+    /// Recorded `videotestsrc` pattern with a frame counter; needs no display.
+    TestSource,
+    /// In-memory test pattern that writes no file; for control-flow tests.
+    Synthetic,
+}
+
+/// Fidelity of the intermediate recording that exports are made from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingQuality {
+    /// Fast and small but visibly lossy.
+    Draft,
+    /// Visually lossless at a moderate size.
+    #[default]
+    High,
+    /// Bit-exact frames with full colour resolution. Keeps UI text crisp
+    /// through GIF palettes, at many times the size.
+    Lossless,
+}
+
+/// Whether a recording stores every frame or only the ones that change.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum FrameRateMode {
+    /// A frame every `1/fps` seconds, changed or not.
+    #[default]
+    Constant,
+    /// Unchanged frames are skipped, keeping timestamps, so idle stretches
+    /// cost nothing and become long GIF frame delays. `fps` is the maximum.
+    Variable {
+        /// Longest stretch without a frame, so players never stall.
+        #[serde(default = "default_max_gap_ms")]
+        max_gap_ms: u32,
+    },
+}
+
+fn default_max_gap_ms() -> u32 {
+    1000
+}
+
+/// Audio recorded alongside the video. Each source becomes its own track.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct AudioOptions {
+    /// What the system plays, via the default output's monitor.
+    #[serde(default)]
+    pub system: Option<AudioSource>,
+    #[serde(default)]
+    pub microphone: Option<AudioSource>,
+}
+
+impl AudioOptions {
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.system.is_some() || self.microphone.is_some()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AudioSource {
+    /// PulseAudio/PipeWire source name; `None` uses the default.
+    #[serde(default)]
+    pub device: Option<String>,
+    /// Linear gain; 1.0 leaves the level unchanged.
+    #[serde(default = "default_gain")]
+    pub gain: f32,
+}
+
+impl Default for AudioSource {
+    fn default() -> Self {
+        Self {
+            device: None,
+            gain: default_gain(),
+        }
+    }
+}
+
+fn default_gain() -> f32 {
+    1.0
+}
@@ -0,0 +1,69 @@
+//! Cursor logs and the effects derived from them.
+
+use crate::types::CaptureRegion;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Derive camera moves and click highlights from a recording's cursor log.
+///
+/// Only X11 recordings have one; Wayland gives no pointer position.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CursorZoom {
+    /// Sidecar written by the recorder, see `cursor_sidecar_path`.
+    pub track: PathBuf,
+    /// Magnification while the cursor is active.
+    #[serde(default = "default_cursor_zoom")]
+    pub zoom: f32,
+    /// Draw a ripple around each click.
+    #[serde(default = "default_true")]
+    pub emphasize_clicks: bool,
+}
+
+fn default_cursor_zoom() -> f32 {
+    2.0
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Cursor activity logged next to a recording. Coordinates are absolute
+/// screen pixels; times are milliseconds since recording started.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct CursorTrack {
+    /// Screen area recorded at the start.
+    pub region: CaptureRegion,
+    pub samples: Vec<CursorSample>,
+    pub clicks: Vec<CursorClick>,
+    /// Where a recorded window moved to, in time order; `region` holds
+    /// until the first entry.
+    #[serde(default)]
+    pub regions: Vec<RegionChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegionChange {
+    pub t_ms: u64,
+    pub region: CaptureRegion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CursorSample {
+    pub t_ms: u64,
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CursorClick {
+    pub t_ms: u64,
+    pub x: i32,
+    pub y: i32,
+    pub button: u8,
+}
+
+/// Location of the cursor log for a recording: `clip.mp4` -> `clip.cursor.json`.
+#[must_use]
+pub fn cursor_sidecar_path(recording: &Path) -> PathBuf {
+    recording.with_extension("cursor.json")
+}
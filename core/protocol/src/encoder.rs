@@ -0,0 +1,124 @@
+//! Encoder commands and events.
+
+use crate::capture::FrameRateMode;
+use crate::cursor::CursorZoom;
+use crate::overlay::{Annotation, CameraKeyframe, Caption, Overlay};
+use crate::types::ErrorKind;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum EncoderCommand {
+    Gif {
+        #[serde(rename = "in")]
+        input: PathBuf,
+        trim: TrimRange,
+        fps: u32,
+        scale_px: Option<u32>,
+        #[serde(rename = "loop")]
+        loop_mode: LoopMode,
+        /// `variable` merges runs of identical frames into one long frame
+        /// delay, at most `max_gap_ms` long; `constant` keeps every frame.
+        #[serde(default)]
+        frame_rate: FrameRateMode,
+        captions: Vec<Caption>,
+        #[serde(default)]
+        overlays: Vec<Overlay>,
+        #[serde(default)]
+        annotations: Vec<Annotation>,
+        #[serde(default)]
+        camera: Vec<CameraKeyframe>,
+        #[serde(default)]
+        cursor_zoom: Option<CursorZoom>,
+        out: PathBuf,
+    },
+    Mp4 {
+        #[serde(rename = "in")]
+        input: PathBuf,
+        trim: TrimRange,
+        fps: u32,
+        scale_px: Option<u32>,
+        quality: f32,
+        captions: Vec<Caption>,
+        #[serde(default)]
+        subtitles: SubtitleMode,
+        #[serde(default)]
+        audio: ExportAudio,
+        #[serde(default)]
+        overlays: Vec<Overlay>,
+        #[serde(default)]
+        annotations: Vec<Annotation>,
+        #[serde(default)]
+        camera: Vec<CameraKeyframe>,
+        #[serde(default)]
+        cursor_zoom: Option<CursorZoom>,
+        out: PathBuf,
+    },
+    Webp {
+        #[serde(rename = "in")]
+        input: PathBuf,
+        trim: TrimRange,
+        fps: u32,
+        scale_px: Option<u32>,
+        quality: f32,
+        lossless: bool,
+        captions: Vec<Caption>,
+        #[serde(default)]
+        overlays: Vec<Overlay>,
+        #[serde(default)]
+        annotations: Vec<Annotation>,
+        #[serde(default)]
+        camera: Vec<CameraKeyframe>,
+        #[serde(default)]
+        cursor_zoom: Option<CursorZoom>,
+        out: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum EncoderEvent {
+    Progress { percent: u32 },
+    Done { path: PathBuf },
+    Error { kind: ErrorKind, hint: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrimRange {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LoopMode {
+    Normal,
+    Pingpong,
+    Once,
+}
+
+/// How MP4 exports carry captions.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleMode {
+    /// Render captions into the video frames.
+    #[default]
+    Burn,
+    /// Embed captions as a `mov_text` subtitle track.
+    Soft,
+}
+
+/// What MP4 exports do with the recording's audio tracks.
+/// GIF and WebP never carry audio.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportAudio {
+    /// Mix all tracks and normalize loudness.
+    #[default]
+    Normalize,
+    /// Mix all tracks at their recorded levels.
+    Keep,
+    /// Drop audio.
+    Mute,
+}
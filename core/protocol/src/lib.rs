@@ -3,9 +3,17 @@
 //! Version: 1
 //! All messages are newline-delimited JSON for easy parsing and logging.
 
+mod capture;
+mod cursor;
+mod encoder;
+mod overlay;
 mod parsing;
 mod types;
 
+pub use capture::*;
+pub use cursor::*;
+pub use encoder::*;
+pub use overlay::*;
 pub use parsing::*;
 pub use types::*;
 
@@ -26,8 +34,16 @@ mod tests {
                 width: 640,
                 height: 360,
             },
+            target: CaptureTarget::Region,
             fps: 30,
             cursor: true,
+            backend: CaptureBackendKind::Auto,
+            audio: AudioOptions::default(),
+            quality: RecordingQuality::High,
+            frame_rate: FrameRateMode::Constant,
+            delay_ms: 0,
+            max_duration_ms: None,
+            max_bytes: None,
             out: PathBuf::from("/tmp/clip.mp4"),
         };
 
@@ -48,7 +64,12 @@ mod tests {
             fps: 15,
             scale_px: Some(480),
             loop_mode: LoopMode::Pingpong,
+            frame_rate: FrameRateMode::Variable { max_gap_ms: 1000 },
             captions: vec![],
+            overlays: vec![],
+            annotations: vec![],
+            camera: vec![],
+            cursor_zoom: None,
             out: PathBuf::from("/tmp/out.gif"),
         };
 
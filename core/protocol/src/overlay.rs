@@ -0,0 +1,123 @@
+//! What exports draw over the recording: captions, images, shapes and
+//! camera moves.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Caption {
+    pub text: String,
+    pub font: String,
+    pub style: CaptionStyle,
+    pub rect: CaptionRect,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(default)]
+    pub animation: CaptionAnimation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaptionStyle {
+    pub color: String,
+    pub stroke: String,
+    pub size: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaptionRect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CaptionAnimation {
+    #[default]
+    None,
+    Fade,
+}
+
+/// PNG or SVG image composited over the video, e.g. a logo or watermark.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Overlay {
+    pub source: PathBuf,
+    /// Placement; the image is scaled to fit inside the box and centred in
+    /// it, keeping its aspect ratio. With `h` of 0 only the width counts and
+    /// the image hangs from the top-left corner.
+    pub rect: CaptionRect,
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(default)]
+    pub fade_ms: u64,
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+/// Timed shape drawn over the video to point at or hide something.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Annotation {
+    pub shape: AnnotationShape,
+    /// Area covered; arrows run from (x, y) to (x + w, y + h), so w/h may be negative.
+    pub rect: CaptionRect,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    #[serde(default = "default_annotation_color")]
+    pub color: String,
+    /// Line width in output pixels for arrows, rectangles and ellipses.
+    #[serde(default = "default_annotation_stroke")]
+    pub stroke: u32,
+}
+
+fn default_annotation_color() -> String {
+    "#FF3B30".to_string()
+}
+
+fn default_annotation_stroke() -> u32 {
+    4
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationShape {
+    Arrow,
+    Rect,
+    Ellipse,
+    /// Dim everything outside the rect.
+    Highlight,
+    /// Redact the rect with coarse blocks.
+    Pixelate,
+    /// Redact the rect with a heavy blur.
+    Blur,
+}
+
+/// Visible part of the recording at a point in time. Between keyframes the
+/// view pans and zooms, producing a Ken Burns style camera move.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CameraKeyframe {
+    pub at_ms: u64,
+    /// Part of the source frame to show. The view keeps the source aspect
+    /// ratio, so a rect of another shape is shown whole, centred, with some
+    /// of its surroundings.
+    pub rect: CaptionRect,
+    /// Curve used to travel from the previous keyframe to this one.
+    #[serde(default)]
+    pub easing: Easing,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Stay on the previous keyframe, then cut.
+    Hold,
+}
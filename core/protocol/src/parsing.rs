@@ -1,4 +1,5 @@
-use crate::types::{EncoderCommand, EncoderEvent, RecorderCommand, RecorderEvent};
+use crate::encoder::{EncoderCommand, EncoderEvent};
+use crate::types::{RecorderCommand, RecorderEvent};
 use serde::Serialize;
 use serde_json::Error;
 
@@ -1,128 +1,143 @@
+//! Recorder commands and events.
+
+use crate::capture::{
+    AudioOptions, CaptureBackendKind, CaptureTarget, FrameRateMode, MonitorInfo, RecordingQuality,
+    WindowInfo,
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "cmd", rename_all = "lowercase")]
 pub enum RecorderCommand {
     Start {
+        /// Screen area to record; ignored unless `target` is a region.
         region: CaptureRegion,
+        #[serde(default)]
+        target: CaptureTarget,
         fps: u32,
         #[serde(default)]
         cursor: bool,
+        #[serde(default)]
+        backend: CaptureBackendKind,
+        #[serde(default)]
+        audio: AudioOptions,
+        #[serde(default)]
+        quality: RecordingQuality,
+        #[serde(default)]
+        frame_rate: FrameRateMode,
+        /// Wait this long before capturing, reporting `Countdown` meanwhile.
+        /// `Stop` during the wait cancels the recording.
+        #[serde(default)]
+        delay_ms: u64,
+        /// Stop on its own after this much recorded time, pauses excluded.
+        #[serde(default)]
+        max_duration_ms: Option<u64>,
+        /// Stop on its own once the file grows this large.
+        #[serde(default)]
+        max_bytes: Option<u64>,
         out: PathBuf,
     },
+    /// Suspend capture; the paused span is left out of the recording.
+    Pause,
+    Resume,
     Stop,
+    /// Stop and throw the recording away, answered with `Cancelled`. During
+    /// the countdown this is the same as `Stop`.
+    Cancel,
+    /// Finalize a recording left behind by a recorder that crashed, see
+    /// `recording_lock_path`.
+    Recover {
+        path: PathBuf,
+    },
+    /// Report the monitors and windows that can be recorded as `Targets`.
+    #[serde(rename = "list_targets")]
+    ListTargets,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "event", rename_all = "lowercase")]
 pub enum RecorderEvent {
-    Started { pts_ms: u64 },
-    Progress { pts_ms: u64 },
-    Stopped { duration_ms: u64, path: PathBuf },
-    Error { kind: ErrorKind, hint: String },
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct CaptureRegion {
-    pub x: i32,
-    pub y: i32,
-    pub width: u32,
-    pub height: u32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "cmd", rename_all = "lowercase")]
-pub enum EncoderCommand {
-    Gif {
-        #[serde(rename = "in")]
-        input: PathBuf,
-        trim: TrimRange,
-        fps: u32,
-        scale_px: Option<u32>,
-        #[serde(rename = "loop")]
-        loop_mode: LoopMode,
-        captions: Vec<Caption>,
-        out: PathBuf,
+    /// A delayed start is pending; capture begins in `remaining_ms`.
+    Countdown {
+        remaining_ms: u64,
     },
-    Mp4 {
-        #[serde(rename = "in")]
-        input: PathBuf,
-        trim: TrimRange,
-        fps: u32,
-        scale_px: Option<u32>,
-        quality: f32,
-        captions: Vec<Caption>,
-        out: PathBuf,
+    /// Stopped during the countdown, or cancelled; nothing was kept.
+    Cancelled,
+    Started {
+        pts_ms: u64,
+        /// Expected growth of the recording, audio included.
+        #[serde(default)]
+        bytes_per_sec: u64,
+        /// Space left where the recording is written, if known.
+        #[serde(default)]
+        free_bytes: Option<u64>,
     },
-    Webp {
-        #[serde(rename = "in")]
-        input: PathBuf,
-        trim: TrimRange,
-        fps: u32,
-        scale_px: Option<u32>,
-        quality: f32,
-        lossless: bool,
-        captions: Vec<Caption>,
-        out: PathBuf,
+    Progress {
+        pts_ms: u64,
+        /// Frames the source skipped so far.
+        #[serde(default)]
+        dropped_frames: u64,
+        /// Frames per second actually delivered.
+        #[serde(default)]
+        actual_fps: f32,
+    },
+    Paused {
+        pts_ms: u64,
+    },
+    Resumed {
+        pts_ms: u64,
+    },
+    /// `duration_ms` is the media duration of the finalized file.
+    Stopped {
+        duration_ms: u64,
+        path: PathBuf,
+        #[serde(default)]
+        dropped_frames: u64,
+        #[serde(default)]
+        actual_fps: f32,
+        #[serde(default)]
+        reason: StopReason,
+    },
+    /// Answer to `ListTargets`.
+    Targets {
+        monitors: Vec<MonitorInfo>,
+        windows: Vec<WindowInfo>,
+    },
+    /// A crashed recording was made playable again.
+    Recovered {
+        path: PathBuf,
+        duration_ms: u64,
+    },
+    Error {
+        kind: ErrorKind,
+        hint: String,
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(tag = "event", rename_all = "lowercase")]
-pub enum EncoderEvent {
-    Progress { percent: u32 },
-    Done { path: PathBuf },
-    Error { kind: ErrorKind, hint: String },
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct TrimRange {
-    pub start_ms: u64,
-    pub end_ms: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum LoopMode {
-    Normal,
-    Pingpong,
-    Once,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct Caption {
-    pub text: String,
-    pub font: String,
-    pub style: CaptionStyle,
-    pub rect: CaptionRect,
-    pub start_ms: u64,
-    pub end_ms: u64,
-    #[serde(default)]
-    pub animation: CaptionAnimation,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct CaptionStyle {
-    pub color: String,
-    pub stroke: String,
-    pub size: u32,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct CaptionRect {
-    pub x: f32,
-    pub y: f32,
-    pub w: f32,
-    pub h: f32,
+/// Why a recording ended.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StopReason {
+    /// A `stop` command, or the controller closing the recorder's input.
+    #[default]
+    User,
+    /// `max_duration_ms` was reached.
+    TimeLimit,
+    /// `max_bytes` was reached.
+    SizeLimit,
+    /// The disk holding the recording ran out of space.
+    DiskFull,
+    /// Capture failed; what was recorded up to then was kept.
+    Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
-#[serde(rename_all = "lowercase")]
-pub enum CaptionAnimation {
-    #[default]
-    None,
-    Fade,
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct CaptureRegion {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -134,3 +149,11 @@ pub enum ErrorKind {
     IoError,
     UnsupportedPlatform,
 }
+
+/// Marker that a recording is in progress: `clip.mp4` -> `clip.recording.lock`.
+///
+/// It outlives a recorder that crashes, flagging the file for recovery.
+#[must_use]
+pub fn recording_lock_path(recording: &Path) -> PathBuf {
+    recording.with_extension("recording.lock")
+}
@@ -10,8 +10,16 @@ fn test_recorder_command() {
             width: 800,
             height: 600,
         },
+        target: CaptureTarget::Region,
         fps: 60,
         cursor: true,
+        backend: CaptureBackendKind::Auto,
+        audio: AudioOptions::default(),
+        quality: RecordingQuality::High,
+        frame_rate: FrameRateMode::Constant,
+        delay_ms: 0,
+        max_duration_ms: None,
+        max_bytes: None,
         out: PathBuf::from("/tmp/test.mp4"),
     };
 
@@ -32,7 +40,12 @@ fn test_encoder_command() {
         fps: 15,
         scale_px: Some(320),
         loop_mode: LoopMode::Normal,
+        frame_rate: FrameRateMode::Constant,
         captions: vec![],
+        overlays: vec![],
+        annotations: vec![],
+        camera: vec![],
+        cursor_zoom: None,
         out: PathBuf::from("out.gif"),
     };
 
@@ -45,13 +58,19 @@ fn test_encoder_command() {
         fps,
         scale_px,
         loop_mode: _,
+        frame_rate,
         captions: _,
+        overlays: _,
+        annotations: _,
+        camera: _,
+        cursor_zoom: _,
         out,
     } = parsed
     {
         assert_eq!(input, PathBuf::from("in.mp4"));
         assert_eq!(fps, 15);
         assert_eq!(scale_px, Some(320));
+        assert_eq!(frame_rate, FrameRateMode::Constant);
         assert_eq!(out, PathBuf::from("out.gif"));
     } else {
         panic!("Wrong variant parsed");
@@ -60,13 +79,69 @@ fn test_encoder_command() {
 
 #[test]
 fn test_recorder_event_roundtrip() {
-    let evt = RecorderEvent::Started { pts_ms: 1234 };
+    let evt = RecorderEvent::Started {
+        pts_ms: 1234,
+        bytes_per_sec: 750_000,
+        free_bytes: Some(8 << 30),
+    };
     let json = to_jsonl(&evt).expect("serialization failed");
     let parsed = parse_recorder_event(&json).expect("parse failed");
 
-    if let RecorderEvent::Started { pts_ms } = parsed {
+    if let RecorderEvent::Started {
+        pts_ms, free_bytes, ..
+    } = parsed
+    {
         assert_eq!(pts_ms, 1234);
+        assert_eq!(free_bytes, Some(8 << 30));
     } else {
         panic!("Wrong variant");
     }
 }
+
+#[test]
+fn test_mp4_subtitles_default_to_burn() {
+    let json = r#"{"cmd":"mp4","in":"in.mp4","trim":{"start_ms":0,"end_ms":1000},"fps":30,"scale_px":null,"quality":0.8,"captions":[],"out":"out.mp4"}"#;
+    let parsed = parse_encoder_command(json).expect("parse failed");
+
+    if let EncoderCommand::Mp4 {
+        subtitles, audio, ..
+    } = parsed
+    {
+        assert_eq!(subtitles, SubtitleMode::Burn);
+        assert_eq!(audio, ExportAudio::Normalize);
+    } else {
+        panic!("Wrong variant parsed");
+    }
+}
+
+#[test]
+fn test_start_backend_defaults_to_auto() {
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":640,"height":480},"fps":30,"out":"out.mp4"}"#;
+    let parsed = parse_recorder_command(json).expect("parse failed");
+
+    if let RecorderCommand::Start {
+        backend, quality, ..
+    } = parsed
+    {
+        assert_eq!(quality, RecordingQuality::High);
+        assert_eq!(backend, CaptureBackendKind::Auto);
+    } else {
+        panic!("Wrong variant parsed");
+    }
+}
+
+#[test]
+fn test_backend_kind_names() {
+    let json = to_jsonl(&CaptureBackendKind::TestSource).expect("serialization failed");
+    assert_eq!(json.trim(), r#""test_source""#);
+}
+
+#[test]
+fn test_pause_resume_messages() {
+    let pause = parse_recorder_command(r#"{"cmd":"pause"}"#).expect("parse failed");
+    assert_eq!(pause, RecorderCommand::Pause);
+
+    let event = RecorderEvent::Resumed { pts_ms: 1500 };
+    let json = to_jsonl(&event).expect("serialization failed");
+    assert_eq!(json.trim(), r#"{"event":"resumed","pts_ms":1500}"#);
+}
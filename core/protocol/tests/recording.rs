@@ -0,0 +1,196 @@
+//! Recording options and lifecycle messages on the wire.
+
+use mandygif_protocol::*;
+use std::path::PathBuf;
+
+#[test]
+fn test_stopped_reports_frame_stats() {
+    let event = RecorderEvent::Stopped {
+        duration_ms: 2000,
+        path: PathBuf::from("/tmp/test.mp4"),
+        dropped_frames: 3,
+        actual_fps: 28.5,
+        reason: StopReason::User,
+    };
+    let json = to_jsonl(&event).expect("serialization failed");
+    assert_eq!(
+        json.trim(),
+        r#"{"event":"stopped","duration_ms":2000,"path":"/tmp/test.mp4","dropped_frames":3,"actual_fps":28.5,"reason":"user"}"#
+    );
+
+    // Older recorders omit the statistics
+    let legacy =
+        parse_recorder_event(r#"{"event":"progress","pts_ms":100}"#).expect("parse failed");
+    assert_eq!(
+        legacy,
+        RecorderEvent::Progress {
+            pts_ms: 100,
+            dropped_frames: 0,
+            actual_fps: 0.0
+        }
+    );
+}
+
+#[test]
+fn test_start_audio_sources() {
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":640,"height":480},"fps":30,"audio":{"system":{},"microphone":{"device":"alsa_input.usb","gain":0.5}},"out":"out.mp4"}"#;
+    let parsed = parse_recorder_command(json).expect("parse failed");
+
+    if let RecorderCommand::Start { audio, .. } = parsed {
+        assert!(audio.is_enabled());
+        assert_eq!(audio.system, Some(AudioSource::default()));
+        assert_eq!(
+            audio.microphone,
+            Some(AudioSource {
+                device: Some("alsa_input.usb".into()),
+                gain: 0.5
+            })
+        );
+    } else {
+        panic!("Wrong variant parsed");
+    }
+
+    // Video-only by default
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":640,"height":480},"fps":30,"out":"out.mp4"}"#;
+    if let Ok(RecorderCommand::Start { audio, .. }) = parse_recorder_command(json) {
+        assert!(!audio.is_enabled());
+    } else {
+        panic!("Wrong variant parsed");
+    }
+}
+
+#[test]
+fn test_recover_messages() {
+    let cmd = parse_recorder_command(r#"{"cmd":"recover","path":"/tmp/clip.mp4"}"#)
+        .expect("parse failed");
+    assert_eq!(
+        cmd,
+        RecorderCommand::Recover {
+            path: PathBuf::from("/tmp/clip.mp4")
+        }
+    );
+    assert_eq!(
+        recording_lock_path(&PathBuf::from("/tmp/clip.mp4")),
+        PathBuf::from("/tmp/clip.recording.lock")
+    );
+
+    let event = RecorderEvent::Recovered {
+        path: PathBuf::from("/tmp/clip.mp4"),
+        duration_ms: 4200,
+    };
+    let json = to_jsonl(&event).expect("serialization failed");
+    assert_eq!(
+        json.trim(),
+        r#"{"event":"recovered","path":"/tmp/clip.mp4","duration_ms":4200}"#
+    );
+}
+
+#[test]
+fn test_variable_frame_rate() {
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":640,"height":480},"fps":30,"frame_rate":{"mode":"variable"},"out":"out.mp4"}"#;
+    let parsed = parse_recorder_command(json).expect("parse failed");
+
+    if let RecorderCommand::Start { frame_rate, .. } = parsed {
+        assert_eq!(frame_rate, FrameRateMode::Variable { max_gap_ms: 1000 });
+    } else {
+        panic!("Wrong variant parsed");
+    }
+
+    let json = to_jsonl(&FrameRateMode::Constant).expect("serialization failed");
+    assert_eq!(json.trim(), r#"{"mode":"constant"}"#);
+}
+
+#[test]
+fn test_delayed_start() {
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":640,"height":480},"fps":30,"delay_ms":3000,"out":"out.mp4"}"#;
+    let parsed = parse_recorder_command(json).expect("parse failed");
+
+    if let RecorderCommand::Start { delay_ms, .. } = parsed {
+        assert_eq!(delay_ms, 3000);
+    } else {
+        panic!("Wrong variant parsed");
+    }
+
+    let json =
+        to_jsonl(&RecorderEvent::Countdown { remaining_ms: 900 }).expect("serialization failed");
+    assert_eq!(json.trim(), r#"{"event":"countdown","remaining_ms":900}"#);
+    let json = to_jsonl(&RecorderEvent::Cancelled).expect("serialization failed");
+    assert_eq!(json.trim(), r#"{"event":"cancelled"}"#);
+    let cancel = parse_recorder_command(r#"{"cmd":"cancel"}"#).expect("parse failed");
+    assert_eq!(cancel, RecorderCommand::Cancel);
+}
+
+#[test]
+fn test_recording_limits() {
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":640,"height":480},"fps":30,"max_duration_ms":600000,"out":"out.mp4"}"#;
+    let parsed = parse_recorder_command(json).expect("parse failed");
+
+    if let RecorderCommand::Start {
+        max_duration_ms,
+        max_bytes,
+        ..
+    } = parsed
+    {
+        assert_eq!(max_duration_ms, Some(600_000));
+        assert_eq!(max_bytes, None);
+    } else {
+        panic!("Wrong variant parsed");
+    }
+
+    // Older recorders only ever stopped on request
+    let legacy = r#"{"event":"stopped","duration_ms":2000,"path":"out.mp4"}"#;
+    let parsed = parse_recorder_event(legacy).expect("parse failed");
+    assert!(matches!(
+        parsed,
+        RecorderEvent::Stopped {
+            reason: StopReason::User,
+            ..
+        }
+    ));
+
+    let json = to_jsonl(&StopReason::DiskFull).expect("serialization failed");
+    assert_eq!(json.trim(), r#""disk_full""#);
+}
+
+#[test]
+fn test_capture_targets() {
+    let json = r#"{"cmd":"start","region":{"x":0,"y":0,"width":0,"height":0},"target":{"kind":"monitor","monitor":"HDMI-1"},"fps":30,"out":"out.mp4"}"#;
+    let parsed = parse_recorder_command(json).expect("parse failed");
+    if let RecorderCommand::Start { target, .. } = parsed {
+        assert_eq!(
+            target,
+            CaptureTarget::Monitor {
+                monitor: MonitorId::Name("HDMI-1".into())
+            }
+        );
+    } else {
+        panic!("Wrong variant parsed");
+    }
+
+    let target = CaptureTarget::Monitor {
+        monitor: MonitorId::Index(1),
+    };
+    let json = to_jsonl(&target).expect("serialization failed");
+    assert_eq!(json.trim(), r#"{"kind":"monitor","monitor":1}"#);
+    let json = to_jsonl(&CaptureTarget::Window { xid: 0x0340_0007 }).expect("serialization failed");
+    assert_eq!(json.trim(), r#"{"kind":"window","xid":54525959}"#);
+
+    let cmd = parse_recorder_command(r#"{"cmd":"list_targets"}"#).expect("parse failed");
+    assert_eq!(cmd, RecorderCommand::ListTargets);
+    let event = RecorderEvent::Targets {
+        monitors: vec![MonitorInfo {
+            index: 0,
+            name: "eDP-1".into(),
+            region: CaptureRegion {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            },
+            primary: true,
+        }],
+        windows: vec![],
+    };
+    let json = to_jsonl(&event).expect("serialization failed");
+    assert_eq!(parse_recorder_event(&json).expect("parse failed"), event);
+}
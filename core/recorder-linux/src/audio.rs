@@ -0,0 +1,101 @@
+//! Audio tracks muxed next to the video.
+
+use anyhow::{Context, Result};
+use gstreamer as gst;
+use mandygif_protocol::{AudioOptions, AudioSource};
+use std::fmt::Write;
+
+/// PulseAudio name for the monitor of the default output; PipeWire's pulse
+/// server understands it too.
+const DEFAULT_MONITOR: &str = "@DEFAULT_MONITOR@";
+
+/// AAC encoders in order of preference.
+const AAC_ENCODERS: [&str; 3] = ["fdkaacenc", "avenc_aac", "voaacenc"];
+
+/// Where audio samples come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioInput {
+    /// The sound server, via `pulsesrc`.
+    Pulse,
+    /// `audiotestsrc` ticks in place of every device, for headless testing.
+    TestTone,
+}
+
+/// First installed AAC encoder.
+///
+/// # Errors
+/// Returns error if none of the supported encoders is available.
+pub fn aac_encoder() -> Result<&'static str> {
+    AAC_ENCODERS
+        .into_iter()
+        .find(|name| gst::ElementFactory::find(name).is_some())
+        .context("No AAC encoder found - install gstreamer1.0-libav")
+}
+
+/// Pipeline branches feeding each requested source into `mux` as its own track.
+///
+/// Sources are live and stamped with pipeline running time, like the video,
+/// so the tracks stay in sync across pauses without extra work.
+#[must_use]
+pub fn branches(audio: &AudioOptions, input: AudioInput, encoder: &str) -> String {
+    let sources = [
+        (audio.system.as_ref(), Some(DEFAULT_MONITOR)),
+        (audio.microphone.as_ref(), None),
+    ];
+
+    let mut desc = String::new();
+    for (source, default_device) in sources {
+        let Some(source) = source else { continue };
+        let _ = write!(
+            desc,
+            " {} ! \
+             audioconvert ! audioresample ! \
+             volume volume={} ! \
+             audio/x-raw,rate=48000,channels=2 ! \
+             {encoder} ! aacparse ! queue ! mux.",
+            element(source, input, default_device),
+            source.gain.max(0.0),
+        );
+    }
+    desc
+}
+
+fn element(source: &AudioSource, input: AudioInput, default_device: Option<&str>) -> String {
+    match input {
+        AudioInput::TestTone => "audiotestsrc is-live=true wave=ticks".to_string(),
+        AudioInput::Pulse => match source.device.as_deref().or(default_device) {
+            // The pipeline clock stays with the system clock shared by the video
+            Some(device) => format!("pulsesrc device=\"{device}\" provide-clock=false"),
+            None => "pulsesrc provide-clock=false".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_branch_per_source() {
+        assert_eq!(
+            branches(&AudioOptions::default(), AudioInput::Pulse, "avenc_aac"),
+            ""
+        );
+
+        let audio = AudioOptions {
+            system: Some(AudioSource::default()),
+            microphone: Some(AudioSource {
+                device: Some("alsa_input.usb".into()),
+                gain: 1.5,
+            }),
+        };
+        let desc = branches(&audio, AudioInput::Pulse, "avenc_aac");
+        assert_eq!(desc.matches("mux.").count(), 2);
+        assert!(desc.contains("pulsesrc device=\"@DEFAULT_MONITOR@\""));
+        assert!(desc.contains("pulsesrc device=\"alsa_input.usb\""));
+        assert!(desc.contains("volume volume=1.5 "));
+
+        let tones = branches(&audio, AudioInput::TestTone, "avenc_aac");
+        assert!(!tones.contains("pulsesrc"));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::portal::{DesktopPortal, ScreenCastPortal};
+use crate::Recorder;
+use anyhow::{bail, Context, Result};
+use mandygif_capture::{Capabilities, CaptureBackend, CaptureConfig, CaptureStats};
+
+/// Where a GStreamer recording gets its frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GstSource {
+    /// `ximagesrc` capture of an X11 screen region.
+    X11,
+    /// Portal screen cast over PipeWire, for Wayland sessions.
+    Wayland,
+    /// `videotestsrc` pattern with frame counter, for headless testing.
+    TestPattern,
+}
+
+/// GStreamer capture to H.264/MP4.
+pub struct GstBackend {
+    source: GstSource,
+    portal: Box<dyn ScreenCastPortal>,
+    recorder: Option<Recorder>,
+    last: CaptureStats,
+}
+
+impl GstBackend {
+    #[must_use]
+    pub fn new(source: GstSource) -> Self {
+        Self {
+            source,
+            portal: Box::new(DesktopPortal),
+            recorder: None,
+            last: CaptureStats::default(),
+        }
+    }
+
+    /// Negotiate Wayland sessions through `portal` instead of the desktop's.
+    #[must_use]
+    pub fn with_portal(mut self, portal: Box<dyn ScreenCastPortal>) -> Self {
+        self.portal = portal;
+        self
+    }
+}
+
+impl CaptureBackend for GstBackend {
+    fn name(&self) -> &'static str {
+        match self.source {
+            GstSource::X11 => "x11",
+            GstSource::Wayland => "wayland",
+            GstSource::TestPattern => "test-source",
+        }
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            pause: true,
+            cursor: self.source != GstSource::TestPattern,
+            audio: true,
+            window: self.source == GstSource::X11,
+        }
+    }
+
+    fn start(&mut self, config: &CaptureConfig) -> Result<()> {
+        if self.recorder.is_some() {
+            bail!("Already recording");
+        }
+        config.validate()?;
+        Recorder::init()?;
+        let recorder = match self.source {
+            GstSource::X11 => Recorder::start(config)?,
+            GstSource::Wayland => {
+                let grant = self.portal.open(config.cursor)?;
+                Recorder::start_pipewire(grant, config)?
+            }
+            GstSource::TestPattern => Recorder::start_test_source(config)?,
+        };
+        self.recorder = Some(recorder);
+        Ok(())
+    }
+
+    fn pause(&mut self) -> Result<()> {
+        self.recorder.as_mut().context("Not recording")?.pause()
+    }
+
+    fn resume(&mut self) -> Result<()> {
+        self.recorder.as_mut().context("Not recording")?.resume()
+    }
+
+    fn stop(&mut self) -> Result<CaptureStats> {
+        let recorder = self.recorder.take().context("Not recording")?;
+        self.last = recorder.stop()?;
+        Ok(self.last)
+    }
+
+    fn stats(&self) -> CaptureStats {
+        self.recorder.as_ref().map_or(self.last, Recorder::stats)
+    }
+
+    fn check(&self) -> Result<()> {
+        self.recorder.as_ref().map_or(Ok(()), Recorder::check)
+    }
+}
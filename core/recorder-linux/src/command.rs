@@ -0,0 +1,164 @@
+//! Answers to the commands read from stdin.
+
+use crate::session::{begin, discard, failure, stop, Limits, Pending, Session};
+use anyhow::anyhow;
+use mandygif_capture::{CaptureBackend, CaptureConfig, SyntheticBackend};
+use mandygif_protocol::*;
+use mandygif_recorder_linux::{
+    list_monitors, list_windows, recover, resolve_target, GstBackend, GstSource, Recorder,
+};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Pick the capture implementation for a start request.
+fn select_backend(kind: CaptureBackendKind) -> Box<dyn CaptureBackend> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some_and(|v| !v.is_empty());
+    match kind {
+        CaptureBackendKind::Auto if wayland => Box::new(GstBackend::new(GstSource::Wayland)),
+        CaptureBackendKind::Auto | CaptureBackendKind::X11 => {
+            Box::new(GstBackend::new(GstSource::X11))
+        }
+        CaptureBackendKind::Wayland => Box::new(GstBackend::new(GstSource::Wayland)),
+        CaptureBackendKind::TestSource => Box::new(GstBackend::new(GstSource::TestPattern)),
+        CaptureBackendKind::Synthetic => Box::new(SyntheticBackend::default()),
+    }
+}
+
+pub fn handle_command(
+    line: &str,
+    active: &mut Option<Session>,
+    pending: &mut Option<Pending>,
+) -> RecorderEvent {
+    let cmd = match parse_recorder_command(line) {
+        Ok(cmd) => cmd,
+        Err(e) => return failure(ErrorKind::InvalidInput, &e.into()),
+    };
+
+    match cmd {
+        RecorderCommand::Start {
+            region,
+            target,
+            fps,
+            cursor,
+            backend,
+            audio,
+            quality,
+            frame_rate,
+            delay_ms,
+            max_duration_ms,
+            max_bytes,
+            out,
+        } => {
+            if active.is_some() || pending.is_some() {
+                return failure(ErrorKind::InvalidInput, &anyhow!("Already recording"));
+            }
+            let backend = select_backend(backend);
+            info!("Using {} capture backend", backend.name());
+            if audio.is_enabled() && !backend.capabilities().audio {
+                return failure(
+                    ErrorKind::InvalidInput,
+                    &anyhow!("The {} backend cannot record audio", backend.name()),
+                );
+            }
+            if matches!(target, CaptureTarget::Window { .. }) && !backend.capabilities().window {
+                return failure(
+                    ErrorKind::InvalidInput,
+                    &anyhow!("The {} backend cannot follow a window", backend.name()),
+                );
+            }
+            // Monitors and windows are looked up now; they may move later
+            let (region, window) = if target == CaptureTarget::Region {
+                (region, None)
+            } else {
+                match resolve_target(&target, region) {
+                    Ok(resolved) => (resolved.region, resolved.window),
+                    Err(e) => return failure(ErrorKind::InvalidInput, &e),
+                }
+            };
+            let config = CaptureConfig {
+                region,
+                window,
+                fps,
+                cursor,
+                audio,
+                quality,
+                frame_rate,
+                out,
+            };
+            let limits = Limits {
+                max_duration_ms,
+                max_bytes,
+            };
+            if delay_ms == 0 {
+                return begin(backend, config, limits, active);
+            }
+            // Bad regions should fail now, not once the countdown is over
+            if let Err(e) = config.validate() {
+                return failure(ErrorKind::InvalidInput, &e);
+            }
+            info!("Recording starts in {} ms", delay_ms);
+            *pending = Some(Pending {
+                backend,
+                config,
+                limits,
+                at: Instant::now() + Duration::from_millis(delay_ms),
+            });
+            RecorderEvent::Countdown {
+                remaining_ms: delay_ms,
+            }
+        }
+        RecorderCommand::Pause | RecorderCommand::Resume => {
+            let Some(session) = active.as_mut() else {
+                return failure(ErrorKind::InvalidInput, &anyhow!("Not recording"));
+            };
+            let pausing = cmd == RecorderCommand::Pause;
+            let result = if pausing {
+                session.backend.pause()
+            } else {
+                session.backend.resume()
+            };
+            let pts_ms = session.backend.stats().duration_ms;
+            match result {
+                Ok(()) if pausing => RecorderEvent::Paused { pts_ms },
+                Ok(()) => RecorderEvent::Resumed { pts_ms },
+                Err(e) => failure(ErrorKind::InvalidInput, &e),
+            }
+        }
+        RecorderCommand::Stop => {
+            if pending.take().is_some() {
+                info!("Countdown cancelled");
+                return RecorderEvent::Cancelled;
+            }
+            match active.take() {
+                Some(session) => stop(session, StopReason::User),
+                None => failure(ErrorKind::InvalidInput, &anyhow!("Not recording")),
+            }
+        }
+        RecorderCommand::Cancel => {
+            if pending.take().is_some() {
+                info!("Countdown cancelled");
+                return RecorderEvent::Cancelled;
+            }
+            match active.take() {
+                Some(session) => discard(session),
+                None => failure(ErrorKind::InvalidInput, &anyhow!("Not recording")),
+            }
+        }
+        RecorderCommand::Recover { path } => match Recorder::init().and_then(|()| recover(&path)) {
+            Ok((path, duration_ms)) => RecorderEvent::Recovered { path, duration_ms },
+            Err(e) => failure(ErrorKind::IoError, &e),
+        },
+        RecorderCommand::ListTargets => {
+            let monitors = match list_monitors() {
+                Ok(monitors) => monitors,
+                Err(e) => return failure(ErrorKind::UnsupportedPlatform, &e),
+            };
+            // A window manager without a client list still has monitors
+            let windows = list_windows().unwrap_or_else(|e| {
+                warn!("Cannot list windows: {:#}", e);
+                Vec::new()
+            });
+            RecorderEvent::Targets { monitors, windows }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Pointer activity for the cursor sidecar, from `XInput` 2 raw events so
+//! that even the shortest click is caught.
+//!
+//! X11 only: a portal screen cast gives no pointer position outside the
+//! stream, so Wayland recordings get no sidecar.
+
+use crate::targets::window_region;
+use anyhow::{Context, Result};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use mandygif_protocol::{CaptureRegion, CursorClick, CursorSample, CursorTrack, RegionChange};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use x11rb::connection::Connection;
+use x11rb::protocol::xinput::{ConnectionExt as _, Device, EventMask, XIEventMask};
+use x11rb::protocol::xproto::{self, ChangeWindowAttributesAux, ConnectionExt as _};
+use x11rb::protocol::Event;
+use x11rb::rust_connection::RustConnection;
+
+/// Logs the X pointer on a background thread for the cursor sidecar.
+pub struct CursorLogger {
+    running: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    handle: JoinHandle<CursorTrack>,
+}
+
+impl CursorLogger {
+    /// Start logging pointer moves and button presses over `region`, and
+    /// the moves of `window` if one is being recorded.
+    ///
+    /// Positions are logged in screen coordinates; the encoder maps them
+    /// to the area recorded at the time. Events are stamped with the
+    /// running time of `pipeline`, the clock the frames are stamped with,
+    /// and picked up once per frame at `fps`.
+    ///
+    /// # Errors
+    /// Returns error if the X server cannot be reached or lacks `XInput` 2.1.
+    pub fn start(
+        region: &CaptureRegion,
+        window: Option<u64>,
+        pipeline: &gst::Pipeline,
+        fps: u32,
+    ) -> Result<Self> {
+        let (conn, screen) = x11rb::connect(None).context("Failed to connect to X server")?;
+        let root = conn.setup().roots[screen].root;
+
+        // Since 2.1 raw events reach the root window even during grabs
+        conn.xinput_xi_query_version(2, 1)?
+            .reply()
+            .context("XInput 2.1 is required to log the cursor")?;
+        conn.xinput_xi_select_events(
+            root,
+            &[EventMask {
+                deviceid: Device::ALL_MASTER.into(),
+                mask: vec![XIEventMask::RAW_MOTION | XIEventMask::RAW_BUTTON_PRESS],
+            }],
+        )?
+        .check()?;
+
+        let window = window
+            .map(u32::try_from)
+            .transpose()
+            .context("Not an X11 window id")?;
+        if let Some(window) = window {
+            let aux =
+                ChangeWindowAttributesAux::new().event_mask(xproto::EventMask::STRUCTURE_NOTIFY);
+            conn.change_window_attributes(window, &aux)?.check()?;
+        }
+
+        // Queued X events are drained in between, so none are lost
+        let poll_interval = Duration::from_secs(1) / fps.max(1);
+        let pipeline = pipeline.clone();
+        let running = Arc::new(AtomicBool::new(true));
+        let paused = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&running);
+        let pause_flag = Arc::clone(&paused);
+        let mut track = CursorTrack {
+            region: region.clone(),
+            ..CursorTrack::default()
+        };
+
+        let handle = thread::spawn(move || {
+            let mut current = track.region.clone();
+            let mut last = None;
+            let mut moved = true;
+            let mut t_ms = 0;
+
+            while flag.load(Ordering::Relaxed) {
+                // Running time skips paused spans, as the frames do; while
+                // paused, window moves take effect from the pause on
+                let paused = pause_flag.load(Ordering::Relaxed);
+                if !paused {
+                    t_ms = pipeline
+                        .current_running_time()
+                        .map_or(t_ms, |t| t.mseconds());
+                }
+
+                loop {
+                    let event = match conn.poll_for_event() {
+                        Ok(Some(event)) => event,
+                        Ok(None) => break,
+                        Err(_) => return track,
+                    };
+                    match event {
+                        Event::XinputRawMotion(_) => moved = true,
+                        Event::XinputRawButtonPress(press) if !paused => {
+                            let button = u8::try_from(press.detail).unwrap_or(0);
+                            if !(1..=5).contains(&button) {
+                                continue;
+                            }
+                            if let Ok((x, y)) = pointer(&conn, root) {
+                                track.clicks.push(CursorClick { t_ms, x, y, button });
+                            }
+                        }
+                        Event::ConfigureNotify(_) => {
+                            let Some(moved_to) =
+                                window.and_then(|w| window_region(&conn, root, w).ok())
+                            else {
+                                continue;
+                            };
+                            if moved_to != current {
+                                current = moved_to.clone();
+                                track.regions.push(RegionChange {
+                                    t_ms,
+                                    region: moved_to,
+                                });
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+
+                // Raw motion carries no position, so look it up once per pass
+                if moved && !paused {
+                    moved = false;
+                    let Ok((x, y)) = pointer(&conn, root) else {
+                        break;
+                    };
+                    if last != Some((x, y)) {
+                        track.samples.push(CursorSample { t_ms, x, y });
+                        last = Some((x, y));
+                    }
+                }
+
+                thread::sleep(poll_interval);
+            }
+            track
+        });
+
+        Ok(Self {
+            running,
+            paused,
+            handle,
+        })
+    }
+
+    /// Stop logging while the recording is paused.
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    /// Stop logging and write the track as JSON to `path`.
+    ///
+    /// # Errors
+    /// Returns error if the logging thread panicked or the file cannot be written.
+    pub fn finish(self, path: &Path) -> Result<()> {
+        self.running.store(false, Ordering::Relaxed);
+        let track = self
+            .handle
+            .join()
+            .map_err(|_| anyhow::anyhow!("Cursor logger panicked"))?;
+        let json = serde_json::to_string(&track)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write cursor log {}", path.display()))
+    }
+}
+
+/// Pointer position on the root window.
+fn pointer(conn: &RustConnection, root: xproto::Window) -> Result<(i32, i32)> {
+    let reply = conn.query_pointer(root)?.reply()?;
+    Ok((i32::from(reply.root_x), i32::from(reply.root_y)))
+}
@@ -0,0 +1,101 @@
+//! Ending a recording with a playable, seekable file.
+
+use crate::Recorder;
+use anyhow::Result;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use mandygif_capture::{mp4_duration_ms, CaptureStats, Clock};
+use mandygif_protocol::cursor_sidecar_path;
+use std::thread;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+impl Recorder {
+    /// Stop recording and finalize file.
+    ///
+    /// The duration is read back from the finished file when possible.
+    ///
+    /// # Errors
+    /// Returns error if pipeline state change fails.
+    pub fn stop(mut self) -> Result<CaptureStats> {
+        // Paused live sources push nothing, so EOS would never reach the muxer
+        if self.timeline.is_paused() {
+            self.resume()?;
+        }
+        self.timeline.stop(self.clock.now_ms())?;
+
+        // An idle screen may not have produced a frame for a while; let one
+        // more through so the file runs up to now
+        if let Some(dedup) = &self.dedup {
+            if let Ok(mut d) = dedup.lock() {
+                d.keep_next();
+            }
+            thread::sleep(Duration::from_millis(2000 / u64::from(self.fps.max(1))));
+        }
+
+        info!("Sending EOS to pipeline...");
+
+        // 1. Send EOS event to the pipeline. This tells qtmux to finalize the file.
+        let eos_sent = self.pipeline.send_event(gst::event::Eos::new());
+        if !eos_sent {
+            error!("Failed to send EOS event");
+        }
+
+        // 2. Wait for the EOS message on the bus. Without it the file stays
+        // fragmented, which plays but cannot be seeked reliably.
+        let mut finalized = false;
+        if let Some(bus) = self.pipeline.bus() {
+            info!("Waiting for EOS...");
+            let msg = bus.timed_pop_filtered(
+                gst::ClockTime::from_seconds(5), // Wait up to 5s
+                &[gst::MessageType::Eos, gst::MessageType::Error],
+            );
+
+            if let Some(msg) = msg {
+                match msg.view() {
+                    gst::MessageView::Eos(_) => {
+                        info!("EOS received, file finalized.");
+                        finalized = true;
+                    }
+                    gst::MessageView::Error(err) => {
+                        error!("Pipeline error during stop: {}", err.error());
+                    }
+                    _ => (),
+                }
+            } else {
+                error!("Timed out waiting for EOS");
+            }
+        }
+
+        // 3. Now it is safe to set NULL
+        self.pipeline.set_state(gst::State::Null)?;
+
+        // 4. Trust the muxer's duration over our own count. An unfinalized
+        // fragmented header reports zero.
+        let mut stats = self.stats();
+        match mp4_duration_ms(&self.out) {
+            Ok(duration_ms) if duration_ms > 0 => stats.duration_ms = duration_ms,
+            Ok(_) => warn!("File not finalized; using buffer timestamps for duration"),
+            Err(e) => warn!("Using buffer timestamps for duration: {:#}", e),
+        }
+
+        match self.lock {
+            Some(lock) if finalized => {
+                if let Err(e) = lock.release() {
+                    warn!("{:#}", e);
+                }
+            }
+            Some(_) => warn!("Leaving {} for recovery", self.out.display()),
+            None => {}
+        }
+
+        // 5. Write the cursor sidecar next to the finished file
+        if let Some(log) = self.cursor_log {
+            if let Err(e) = log.finish(&cursor_sidecar_path(&self.out)) {
+                warn!("Failed to save cursor log: {:#}", e);
+            }
+        }
+
+        Ok(stats)
+    }
+}
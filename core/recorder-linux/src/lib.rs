@@ -1,16 +1,49 @@
 #![allow(clippy::cast_possible_wrap)]
 
-use anyhow::{Context, Result};
+mod audio;
+mod backend;
+mod cursor;
+mod finish;
+mod pipeline;
+mod portal;
+mod recover;
+mod targets;
+
+pub use backend::{GstBackend, GstSource};
+pub use portal::{DesktopPortal, PortalGrant, PortalStream, ScreenCastPortal};
+pub use recover::{recover, recovered_path, remux};
+pub use targets::{list_monitors, list_windows, resolve_target, ResolvedTarget};
+
+use anyhow::{bail, Context, Result};
+use audio::AudioInput;
+use cursor::CursorLogger;
 use gstreamer as gst;
 use gstreamer::prelude::*;
-use mandygif_protocol::CaptureRegion;
-use std::path::Path;
-use std::time::Instant;
-use tracing::{error, info};
+use mandygif_capture::{
+    CaptureConfig, CaptureStats, Clock, FrameDeduper, FrameMeter, RecordingLock, SystemClock,
+    Timeline,
+};
+use mandygif_protocol::FrameRateMode;
+use pipeline::x11_source;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
 
 pub struct Recorder {
     pipeline: gst::Pipeline,
-    start_time: Instant,
+    clock: SystemClock,
+    timeline: Timeline,
+    /// Fed from buffer timestamps at the encoder input.
+    meter: Arc<Mutex<FrameMeter>>,
+    /// Drops unchanged frames at a variable frame rate.
+    dedup: Option<Arc<Mutex<FrameDeduper>>>,
+    fps: u32,
+    out: PathBuf,
+    /// Flags the file for recovery should we crash before finalizing it.
+    lock: Option<RecordingLock>,
+    cursor_log: Option<CursorLogger>,
+    /// Keeps a Wayland screen cast session open until the recorder is dropped.
+    _portal: Option<PortalGrant>,
 }
 
 impl Recorder {
@@ -22,92 +55,124 @@ impl Recorder {
         gst::init().context("Failed to initialize GStreamer")
     }
 
-    /// Start recording a region to a file.
+    /// Start recording a region, or a window if `config.window` is set, plus
+    /// any requested audio, to a file.
     ///
     /// # Errors
     /// Returns error if dimensions are invalid or pipeline cannot be constructed/started.
-    pub fn start(region: &CaptureRegion, fps: u32, cursor: bool, out: &Path) -> Result<Self> {
-        if region.width == 0 || region.height == 0 {
-            return Err(anyhow::anyhow!("Invalid dimensions"));
-        }
+    pub fn start(config: &CaptureConfig) -> Result<Self> {
+        config.validate()?;
+        let region = &config.region;
 
-        // Note: qtmux needs to be sent EOS to write the moov atom (file footer)
-        let desc = format!(
-            "ximagesrc startx={} starty={} endx={} endy={} use-damage=false show-pointer={} ! \
-             video/x-raw,framerate={}/1 ! \
-             videoconvert ! \
-             video/x-raw,format=I420 ! \
-             x264enc speed-preset=ultrafast tune=zerolatency ! \
-             h264parse ! \
-             qtmux name=mux ! \
-             filesink location={} sync=false",
-            region.x,
-            region.y,
-            region.x + region.width as i32 - 1,
-            region.y + region.height as i32 - 1,
-            cursor,
-            fps,
-            out.display()
-        );
+        let source = x11_source(config);
+        let mut recorder = Self::launch(&source, config, AudioInput::Pulse)?;
 
-        info!("Pipeline: {}", desc);
+        // Cursor data is a nice-to-have; never fail the recording over it
+        recorder.cursor_log =
+            CursorLogger::start(region, config.window, &recorder.pipeline, config.fps)
+                .inspect_err(|e| warn!("Cursor logging disabled: {:#}", e))
+                .ok();
 
-        let pipeline = gst::parse::launch(&desc)?
-            .dynamic_cast::<gst::Pipeline>()
-            .map_err(|_| anyhow::anyhow!("Not a pipeline"))?;
+        Ok(recorder)
+    }
 
-        pipeline.set_state(gst::State::Playing)?;
+    /// Record a deterministic test pattern instead of the screen.
+    ///
+    /// Frames have the region's size, with the frame number burned in at the
+    /// top-left and the running time at the bottom-left; at a variable frame
+    /// rate the pattern is left static instead. Requested audio sources
+    /// record test ticks. Needs no display or sound server.
+    ///
+    /// # Errors
+    /// Returns error if dimensions are invalid or pipeline cannot be constructed/started.
+    pub fn start_test_source(config: &CaptureConfig) -> Result<Self> {
+        config.validate()?;
 
-        Ok(Self {
-            pipeline,
-            start_time: Instant::now(),
-        })
+        let mut source = format!(
+            "videotestsrc is-live=true pattern=smpte ! \
+             video/x-raw,width={},height={},framerate={}/1",
+            config.region.width, config.region.height, config.fps,
+        );
+        if config.frame_rate == FrameRateMode::Constant {
+            source.push_str(
+                " ! timeoverlay time-mode=buffer-count valignment=top halignment=left \
+                 ! timeoverlay time-mode=running-time valignment=bottom halignment=left",
+            );
+        }
+        Self::launch(&source, config, AudioInput::TestTone)
+    }
+
+    /// Record a region of a portal screen cast (Wayland).
+    ///
+    /// The cursor is not logged, so these recordings cannot use
+    /// `CursorZoom`.
+    ///
+    /// # Errors
+    /// Returns error if no granted stream contains the region or the
+    /// pipeline cannot be constructed/started.
+    pub fn start_pipewire(grant: PortalGrant, config: &CaptureConfig) -> Result<Self> {
+        let source = grant.source(&config.region, config.fps, config.frame_rate)?;
+        let mut recorder = Self::launch(&source, config, AudioInput::Pulse)?;
+        recorder._portal = Some(grant);
+        Ok(recorder)
     }
 
+    /// File the recording is written to.
     #[must_use]
-    pub fn duration_ms(&self) -> u64 {
-        u64::try_from(self.start_time.elapsed().as_millis()).unwrap_or(0)
+    pub fn path(&self) -> &Path {
+        &self.out
     }
 
-    /// Stop recording and finalize file.
+    /// Frames encoded so far and the media time they span, excluding pauses.
+    #[must_use]
+    pub fn stats(&self) -> CaptureStats {
+        self.meter.lock().map(|m| m.stats()).unwrap_or_default()
+    }
+
+    /// Fail if the pipeline has posted an error since the last check.
     ///
     /// # Errors
-    /// Returns error if pipeline state change fails.
-    pub fn stop(self) -> Result<u64> {
-        info!("Sending EOS to pipeline...");
-        let duration = self.duration_ms();
-
-        // 1. Send EOS event to the pipeline. This tells qtmux to finish the file.
-        let eos_sent = self.pipeline.send_event(gst::event::Eos::new());
-        if !eos_sent {
-            error!("Failed to send EOS event");
+    /// Returns the pipeline's error.
+    pub fn check(&self) -> Result<()> {
+        let Some(msg) = self
+            .pipeline
+            .bus()
+            .and_then(|bus| bus.pop_filtered(&[gst::MessageType::Error]))
+        else {
+            return Ok(());
+        };
+        match msg.view() {
+            gst::MessageView::Error(err) => bail!("Pipeline error: {}", err.error()),
+            _ => Ok(()),
         }
+    }
 
-        // 2. Wait for the EOS message on the bus.
-        // This is CRITICAL. If we stop before this, the MP4 is corrupt (no moov atom).
-        if let Some(bus) = self.pipeline.bus() {
-            info!("Waiting for EOS...");
-            let msg = bus.timed_pop_filtered(
-                gst::ClockTime::from_seconds(5), // Wait up to 5s
-                &[gst::MessageType::Eos, gst::MessageType::Error],
-            );
-
-            if let Some(msg) = msg {
-                match msg.view() {
-                    gst::MessageView::Eos(_) => info!("EOS received, file finalized."),
-                    gst::MessageView::Error(err) => {
-                        error!("Pipeline error during stop: {}", err.error());
-                    }
-                    _ => (),
-                }
-            } else {
-                error!("Timed out waiting for EOS - file might be corrupt");
-            }
+    /// Suspend capture without ending the file.
+    ///
+    /// A paused pipeline stops its running time, and live sources stamp
+    /// buffers with running time, so the file continues seamlessly.
+    ///
+    /// # Errors
+    /// Returns error if not recording, already paused, or the state change fails.
+    pub fn pause(&mut self) -> Result<()> {
+        self.timeline.pause(self.clock.now_ms())?;
+        self.pipeline.set_state(gst::State::Paused)?;
+        if let Some(log) = &self.cursor_log {
+            log.set_paused(true);
         }
+        Ok(())
+    }
 
-        // 3. Now it is safe to set NULL
-        self.pipeline.set_state(gst::State::Null)?;
-
-        Ok(duration)
+    /// Continue a paused capture.
+    ///
+    /// # Errors
+    /// Returns error if not paused or the state change fails.
+    pub fn resume(&mut self) -> Result<()> {
+        self.timeline.resume(self.clock.now_ms())?;
+        self.pipeline.set_state(gst::State::Playing)?;
+        if let Some(log) = &self.cursor_log {
+            log.set_paused(false);
+        }
+        Ok(())
     }
 }
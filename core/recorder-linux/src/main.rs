@@ -0,0 +1,98 @@
+//! Linux screen recorder: capture backends driven by JSONL on stdin/stdout
+
+#![allow(clippy::wildcard_imports)]
+
+mod command;
+mod session;
+
+use anyhow::Result;
+use command::handle_command;
+use mandygif_protocol::*;
+use session::{begin, stop, Pending, Session};
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// How often `Progress` is reported (and limits checked) while recording.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+fn main() -> Result<()> {
+    // Logs go to stderr; stdout carries only protocol events
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter("info")
+        .init();
+
+    info!("recorder-linux starting (protocol v{})", PROTOCOL_VERSION);
+
+    // Read stdin on its own thread so progress keeps flowing between commands
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut stdout = io::stdout();
+    let mut active: Option<Session> = None;
+    let mut pending: Option<Pending> = None;
+    loop {
+        match rx.recv_timeout(PROGRESS_INTERVAL) {
+            Ok(line) => {
+                let event = handle_command(&line, &mut active, &mut pending);
+                send(&mut stdout, &event)?;
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if let Some(p) = &pending {
+                    let remaining = p.at.saturating_duration_since(Instant::now());
+                    let event = RecorderEvent::Countdown {
+                        remaining_ms: remaining.as_millis().try_into().unwrap_or(u64::MAX),
+                    };
+                    send(&mut stdout, &event)?;
+                } else if let Some(session) = &active {
+                    let stats = session.backend.stats();
+                    let event = RecorderEvent::Progress {
+                        pts_ms: stats.duration_ms,
+                        dropped_frames: stats.dropped_frames,
+                        actual_fps: stats.actual_fps(),
+                    };
+                    send(&mut stdout, &event)?;
+
+                    if let Some(reason) = session.limit_reached() {
+                        info!("Stopping recording: {:?}", reason);
+                        if let Some(session) = active.take() {
+                            send(&mut stdout, &stop(session, reason))?;
+                        }
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if let Some(p) = pending.take_if(|p| p.at <= Instant::now()) {
+            send(
+                &mut stdout,
+                &begin(p.backend, p.config, p.limits, &mut active),
+            )?;
+        }
+    }
+
+    // Controller went away mid-recording: still finalize so the file is playable
+    if let Some(session) = active.take() {
+        info!("stdin closed while recording, finalizing");
+        send(&mut stdout, &stop(session, StopReason::User))?;
+    }
+    Ok(())
+}
+
+fn send(stdout: &mut io::Stdout, event: &RecorderEvent) -> Result<()> {
+    let json = to_jsonl(event)?;
+    stdout.write_all(json.as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
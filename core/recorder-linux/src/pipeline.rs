@@ -0,0 +1,171 @@
+//! The `GStreamer` pipeline a recording is encoded by.
+
+use crate::audio::{self, AudioInput};
+use crate::Recorder;
+use anyhow::{Context, Result};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use mandygif_capture::{
+    CaptureConfig, Clock, FrameDeduper, FrameMeter, RecordingLock, SystemClock, Timeline,
+};
+use mandygif_protocol::{FrameRateMode, RecordingQuality};
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+impl Recorder {
+    /// Encode `source` and the configured audio tracks to the output file
+    /// and start the pipeline.
+    pub(crate) fn launch(source: &str, config: &CaptureConfig, input: AudioInput) -> Result<Self> {
+        let (fps, audio, out) = (config.fps, &config.audio, config.out.as_path());
+        let audio_desc = if audio.is_enabled() {
+            audio::branches(audio, input, audio::aac_encoder()?)
+        } else {
+            String::new()
+        };
+
+        let (format, encoder) = encoding(config.quality);
+
+        // Fragments make everything up to the last second playable if we
+        // crash; EOS still rewrites the header into a regular, seekable MP4
+        let desc = format!(
+            "{source} ! \
+             videoconvert ! \
+             capsfilter name=meter caps=video/x-raw,format={format} ! \
+             {encoder} ! \
+             h264parse ! \
+             queue ! \
+             qtmux name=mux fragment-duration=1000 fragment-mode=first-moov-then-finalise ! \
+             filesink location={} sync=false{audio_desc}",
+            out.display()
+        );
+
+        info!("Pipeline: {}", desc);
+
+        let pipeline = gst::parse::launch(&desc)?
+            .dynamic_cast::<gst::Pipeline>()
+            .map_err(|_| anyhow::anyhow!("Not a pipeline"))?;
+
+        let meter_element = pipeline
+            .by_name("meter")
+            .context("Pipeline has no frame meter")?;
+
+        // Skip unchanged frames before the encoder; their neighbours'
+        // timestamps carry through to the file
+        let (meter, dedup) = match config.frame_rate {
+            FrameRateMode::Constant => (FrameMeter::new(fps), None),
+            FrameRateMode::Variable { max_gap_ms } => {
+                let dedup = Arc::new(Mutex::new(FrameDeduper::new(max_gap_ms)));
+                let pad = meter_element
+                    .static_pad("sink")
+                    .context("Frame meter has no sink pad")?;
+                let probe_dedup = Arc::clone(&dedup);
+                pad.add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+                    let keep = info.buffer().map_or(true, |buffer| {
+                        let (Some(pts), Ok(map)) = (buffer.pts(), buffer.map_readable()) else {
+                            return true;
+                        };
+                        probe_dedup
+                            .lock()
+                            .map_or(true, |mut d| d.keep(pts.nseconds(), map.as_slice()))
+                    });
+                    if keep {
+                        gst::PadProbeReturn::Ok
+                    } else {
+                        gst::PadProbeReturn::Drop
+                    }
+                });
+                (FrameMeter::variable(fps, max_gap_ms), Some(dedup))
+            }
+        };
+
+        // Count what reaches the encoder, stamped in pipeline running time
+        let meter = Arc::new(Mutex::new(meter));
+        let pad = meter_element
+            .static_pad("src")
+            .context("Frame meter has no source pad")?;
+        let probe_meter = Arc::clone(&meter);
+        pad.add_probe(gst::PadProbeType::BUFFER, move |_, info| {
+            if let Some(pts) = info.buffer().and_then(|b| b.pts()) {
+                if let Ok(mut m) = probe_meter.lock() {
+                    m.record(pts.nseconds());
+                }
+            }
+            gst::PadProbeReturn::Ok
+        });
+
+        // Flag the file before anything is written to it, so a crash at any
+        // point leaves it marked for recovery
+        let lock = RecordingLock::acquire(out)
+            .inspect_err(|e| warn!("Crash recovery disabled: {:#}", e))
+            .ok();
+        if let Err(e) = pipeline.set_state(gst::State::Playing) {
+            if let Some(lock) = lock {
+                let _ = lock.release();
+            }
+            return Err(e.into());
+        }
+
+        let clock = SystemClock::default();
+        let mut timeline = Timeline::default();
+        timeline.start(clock.now_ms())?;
+
+        Ok(Self {
+            pipeline,
+            clock,
+            timeline,
+            meter,
+            dedup,
+            fps,
+            out: out.to_path_buf(),
+            lock,
+            cursor_log: None,
+            _portal: None,
+        })
+    }
+}
+
+/// `ximagesrc` chain for the recorded region, or window if one is set.
+pub(crate) fn x11_source(config: &CaptureConfig) -> String {
+    let region = &config.region;
+    // XDamage lets ximagesrc copy only what changed, which pays off when
+    // unchanged frames are going to be dropped anyway
+    let use_damage = matches!(config.frame_rate, FrameRateMode::Variable { .. });
+    if let Some(xid) = config.window {
+        // The window may be resized mid-recording; scale it back to the
+        // size the encoder was set up for
+        format!(
+            "ximagesrc xid={} use-damage={} show-pointer={} ! videoscale ! \
+             video/x-raw,width={},height={},framerate={}/1",
+            xid, use_damage, config.cursor, region.width, region.height, config.fps,
+        )
+    } else {
+        format!(
+            "ximagesrc startx={} starty={} endx={} endy={} use-damage={} show-pointer={} ! \
+             video/x-raw,framerate={}/1",
+            region.x,
+            region.y,
+            region.x + region.width as i32 - 1,
+            region.y + region.height as i32 - 1,
+            use_damage,
+            config.cursor,
+            config.fps,
+        )
+    }
+}
+
+/// Raw pixel format and H.264 encoder settings for a recording quality.
+fn encoding(quality: RecordingQuality) -> (&'static str, &'static str) {
+    match quality {
+        RecordingQuality::Draft => ("I420", "x264enc speed-preset=ultrafast tune=zerolatency"),
+        // Constant quality (CRF 18) rather than the default fixed bitrate
+        RecordingQuality::High => (
+            "I420",
+            "x264enc speed-preset=superfast tune=zerolatency pass=qual quantizer=18",
+        ),
+        // QP 0 is lossless; 4:4:4 keeps coloured text edges intact
+        RecordingQuality::Lossless => (
+            "Y444",
+            "x264enc speed-preset=ultrafast tune=zerolatency pass=quant quantizer=0",
+        ),
+    }
+}
@@ -0,0 +1,197 @@
+//! xdg-desktop-portal screen cast negotiation for Wayland sessions.
+
+mod desktop;
+
+pub use desktop::DesktopPortal;
+
+use anyhow::{bail, Result};
+use desktop::SessionGuard;
+use mandygif_protocol::{CaptureRegion, FrameRateMode};
+use std::os::fd::{AsRawFd, OwnedFd};
+
+/// A monitor stream granted by the portal, in logical desktop coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortalStream {
+    pub node_id: u32,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    /// Physical pixels per logical one; frames come in physical pixels.
+    pub scale: f64,
+}
+
+/// An open screen cast session. Dropping it ends the session.
+pub struct PortalGrant {
+    pub streams: Vec<PortalStream>,
+    /// PipeWire remote to connect to; `None` uses the default daemon.
+    pub fd: Option<OwnedFd>,
+    _session: Option<SessionGuard>,
+}
+
+impl PortalGrant {
+    /// A grant not backed by a portal session, for mocks.
+    #[must_use]
+    pub fn new(streams: Vec<PortalStream>, fd: Option<OwnedFd>) -> Self {
+        Self {
+            streams,
+            fd,
+            _session: None,
+        }
+    }
+
+    /// `pipewiresrc` chain producing the region at `fps`, or at most `fps`
+    /// for a variable frame rate.
+    ///
+    /// # Errors
+    /// Returns error if no single granted stream contains the region.
+    pub fn source(
+        &self,
+        region: &CaptureRegion,
+        fps: u32,
+        frame_rate: FrameRateMode,
+    ) -> Result<String> {
+        let (stream, crop) = locate(&self.streams, region)?;
+        let fd = self
+            .fd
+            .as_ref()
+            .map(|fd| format!("fd={} ", fd.as_raw_fd()))
+            .unwrap_or_default();
+        // Compositors only send damaged frames; keepalive repeats the last
+        // one. Constant rate fills the gaps, variable only caps the rate.
+        let (keepalive, rate) = match frame_rate {
+            FrameRateMode::Constant => (1000, format!("videorate ! video/x-raw,framerate={fps}/1")),
+            FrameRateMode::Variable { max_gap_ms } => (
+                max_gap_ms,
+                format!("videorate drop-only=true max-rate={fps}"),
+            ),
+        };
+        Ok(format!(
+            "pipewiresrc {fd}path={} do-timestamp=true keepalive-time={keepalive} ! \
+             videoconvert ! \
+             videocrop left={} top={} right={} bottom={} ! \
+             {rate}",
+            stream.node_id, crop.left, crop.top, crop.right, crop.bottom,
+        ))
+    }
+}
+
+/// Source of screen cast sessions; mocked in tests.
+pub trait ScreenCastPortal: Send {
+    /// Ask the user to share a screen.
+    ///
+    /// # Errors
+    /// Returns `CaptureError::PermissionDenied` if the user declines, or an
+    /// error if the portal is unavailable.
+    fn open(&self, cursor: bool) -> Result<PortalGrant>;
+}
+
+/// Pixels to trim from each side of a stream.
+#[derive(Debug, PartialEq, Eq)]
+struct Crop {
+    left: u32,
+    top: u32,
+    right: u32,
+    bottom: u32,
+}
+
+/// Find the stream that fully contains `region` (physical pixels) and the
+/// crop selecting it from the stream's frames.
+///
+/// A monitor's logical position and size are scaled by its own factor.
+fn locate<'a>(
+    streams: &'a [PortalStream],
+    region: &CaptureRegion,
+) -> Result<(&'a PortalStream, Crop)> {
+    for stream in streams {
+        #[allow(clippy::cast_possible_truncation)]
+        let physical = |logical: f64| (logical * stream.scale).round() as i64;
+        let left = i64::from(region.x) - physical(f64::from(stream.position.0));
+        let top = i64::from(region.y) - physical(f64::from(stream.position.1));
+        let right = physical(f64::from(stream.size.0)) - left - i64::from(region.width);
+        let bottom = physical(f64::from(stream.size.1)) - top - i64::from(region.height);
+        if let (Ok(left), Ok(top), Ok(right), Ok(bottom)) = (
+            u32::try_from(left),
+            u32::try_from(top),
+            u32::try_from(right),
+            u32::try_from(bottom),
+        ) {
+            return Ok((
+                stream,
+                Crop {
+                    left,
+                    top,
+                    right,
+                    bottom,
+                },
+            ));
+        }
+    }
+    bail!("Capture region is not inside a single shared screen")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitors() -> Vec<PortalStream> {
+        vec![
+            PortalStream {
+                node_id: 40,
+                position: (0, 0),
+                size: (1920, 1080),
+                scale: 1.0,
+            },
+            PortalStream {
+                node_id: 41,
+                position: (1920, 0),
+                size: (2560, 1440),
+                scale: 1.0,
+            },
+        ]
+    }
+
+    fn region(x: i32, y: i32, width: u32, height: u32) -> CaptureRegion {
+        CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_crop_on_second_monitor() -> Result<()> {
+        let streams = monitors();
+        let (stream, crop) = locate(&streams, &region(2020, 50, 640, 480))?;
+        assert_eq!(stream.node_id, 41);
+        assert_eq!(
+            crop,
+            Crop {
+                left: 100,
+                top: 50,
+                right: 1820,
+                bottom: 910
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_spanning_monitors_is_rejected() {
+        assert!(locate(&monitors(), &region(1800, 0, 400, 300)).is_err());
+    }
+
+    #[test]
+    fn test_source_without_fd_uses_default_remote() -> Result<()> {
+        let grant = PortalGrant::new(monitors(), None);
+        let source = grant.source(&region(0, 0, 1920, 1080), 30, FrameRateMode::Constant)?;
+        assert!(source.starts_with("pipewiresrc path=40 "));
+        assert!(source.contains("videocrop left=0 top=0 right=0 bottom=0"));
+        assert!(source.ends_with("framerate=30/1"));
+
+        let vfr = FrameRateMode::Variable { max_gap_ms: 2000 };
+        let source = grant.source(&region(0, 0, 1920, 1080), 30, vfr)?;
+        assert!(source.contains("keepalive-time=2000 "));
+        assert!(source.ends_with("videorate drop-only=true max-rate=30"));
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+//! The desktop's xdg-desktop-portal, reached over D-Bus.
+
+use super::{PortalGrant, PortalStream, ScreenCastPortal};
+use anyhow::{anyhow, Context, Result};
+use ashpd::desktop::screencast::{CursorMode, PersistMode, Screencast, SourceType};
+use ashpd::desktop::{ResponseError, Session};
+use ashpd::WindowIdentifier;
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use mandygif_capture::CaptureError;
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use tokio::sync::oneshot;
+
+/// The desktop's portal over D-Bus.
+pub struct DesktopPortal;
+
+impl ScreenCastPortal for DesktopPortal {
+    fn open(&self, cursor: bool) -> Result<PortalGrant> {
+        let (tx, rx) = mpsc::channel();
+        let (close_tx, close_rx) = oneshot::channel();
+
+        // The session must stay open while recording, so its D-Bus runtime
+        // lives on a thread until the grant is dropped.
+        let handle = thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = tx.send(Err(e.into()));
+                    return;
+                }
+            };
+            runtime.block_on(async move {
+                match negotiate(cursor).await {
+                    Ok((session, streams, fd)) => {
+                        let _ = tx.send(Ok((streams, fd)));
+                        let _ = close_rx.await;
+                        let _ = session.close().await;
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(e));
+                    }
+                }
+            });
+        });
+
+        let (mut streams, fd) = rx
+            .recv()
+            .map_err(|_| anyhow!("Portal thread exited unexpectedly"))??;
+        for stream in &mut streams {
+            stream.scale = frame_scale(&fd, stream)?;
+        }
+        Ok(PortalGrant {
+            streams,
+            fd: Some(fd),
+            _session: Some(SessionGuard {
+                close: Some(close_tx),
+                handle: Some(handle),
+            }),
+        })
+    }
+}
+
+/// Closes the portal session when dropped.
+pub(super) struct SessionGuard {
+    close: Option<oneshot::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Some(close) = self.close.take() {
+            let _ = close.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+async fn negotiate(cursor: bool) -> Result<(Session<'static>, Vec<PortalStream>, OwnedFd)> {
+    let proxy = Screencast::new().await.map_err(portal_error)?;
+    let session = proxy.create_session().await.map_err(portal_error)?;
+    let cursor_mode = if cursor {
+        CursorMode::Embedded
+    } else {
+        CursorMode::Hidden
+    };
+
+    proxy
+        .select_sources(
+            &session,
+            cursor_mode,
+            SourceType::Monitor.into(),
+            true,
+            None,
+            PersistMode::DoNot,
+        )
+        .await
+        .and_then(|request| request.response())
+        .map_err(portal_error)?;
+
+    let response = proxy
+        .start(&session, &WindowIdentifier::default())
+        .await
+        .and_then(|request| request.response())
+        .map_err(portal_error)?;
+
+    let streams = response
+        .streams()
+        .iter()
+        .map(|s| {
+            let (w, h) = s.size().context("Portal did not report stream size")?;
+            Ok(PortalStream {
+                node_id: s.pipe_wire_node_id(),
+                position: s.position().unwrap_or_default(),
+                size: (u32::try_from(w)?, u32::try_from(h)?),
+                scale: 1.0,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let fd = proxy
+        .open_pipe_wire_remote(&session)
+        .await
+        .map_err(portal_error)?;
+    Ok((session, streams, fd))
+}
+
+/// Physical pixels per logical one on the monitor behind `stream`, from
+/// the width of its first frame; the portal only reports logical sizes.
+fn frame_scale(fd: &OwnedFd, stream: &PortalStream) -> Result<f64> {
+    let desc = format!(
+        "pipewiresrc fd={} path={} num-buffers=1 ! fakesink name=sink",
+        fd.as_raw_fd(),
+        stream.node_id
+    );
+    let pipeline = gst::parse::launch(&desc)?
+        .dynamic_cast::<gst::Pipeline>()
+        .map_err(|_| anyhow!("Not a pipeline"))?;
+    pipeline.set_state(gst::State::Playing)?;
+    if let Some(bus) = pipeline.bus() {
+        bus.timed_pop_filtered(
+            gst::ClockTime::from_seconds(5),
+            &[gst::MessageType::Eos, gst::MessageType::Error],
+        );
+    }
+    let width = pipeline
+        .by_name("sink")
+        .and_then(|sink| sink.static_pad("sink"))
+        .and_then(|pad| pad.current_caps())
+        .and_then(|caps| caps.structure(0)?.get::<i32>("width").ok());
+    pipeline.set_state(gst::State::Null)?;
+
+    let width = width.context("Screen cast stream sent no frame")?;
+    Ok(f64::from(width) / f64::from(stream.size.0.max(1)))
+}
+
+fn portal_error(e: ashpd::Error) -> anyhow::Error {
+    match e {
+        ashpd::Error::Response(ResponseError::Cancelled) => {
+            CaptureError::PermissionDenied("screen sharing was declined".into()).into()
+        }
+        e => anyhow::Error::new(e).context("Screen cast portal request failed"),
+    }
+}
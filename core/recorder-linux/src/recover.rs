@@ -0,0 +1,119 @@
+//! Finalizing recordings left behind by a crashed recorder.
+
+use anyhow::{bail, Context, Result};
+use gstreamer as gst;
+use gstreamer::prelude::*;
+use mandygif_capture::{mp4_duration_ms, RecordingLock};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{error, info, warn};
+
+/// Turn the fragmented leftovers of a crashed recording into a regular MP4
+/// next to it, returning the new file and its duration.
+///
+/// The result gets a name of its own, so a new recording to the same path
+/// can start while or after this runs. It is the same name every time, so
+/// each recovery replaces the clip the last one left rather than piling
+/// them up. The partial file is removed once remuxed. The lock is cleared even if remuxing fails, since a file
+/// without a single complete fragment holds nothing to recover.
+///
+/// # Errors
+/// Returns error if `recording` is not a stale recording or cannot be remuxed.
+pub fn recover(recording: &Path) -> Result<(PathBuf, u64)> {
+    if !RecordingLock::is_stale(recording) {
+        bail!("{} has nothing to recover", recording.display());
+    }
+    info!("Recovering {}", recording.display());
+
+    let fixed = recovered_path(recording);
+    let result = remux(recording, &fixed).and_then(|()| mp4_duration_ms(&fixed));
+    match &result {
+        Ok(_) => {
+            if let Err(e) = fs::remove_file(recording) {
+                warn!("Failed to remove partial recording: {}", e);
+            }
+        }
+        Err(_) => {
+            let _ = fs::remove_file(&fixed);
+        }
+    }
+    if let Err(e) = RecordingLock::clear(recording) {
+        warn!("{:#}", e);
+    }
+    Ok((fixed, result?))
+}
+
+/// `clip.mp4` -> `clip.recovered.mp4`.
+#[must_use]
+pub fn recovered_path(recording: &Path) -> PathBuf {
+    recording.with_extension("recovered.mp4")
+}
+
+/// Copy every track of `input` into a non-fragmented MP4 at `out`.
+///
+/// # Errors
+/// Returns error if the pipeline cannot be built or fails while running.
+pub fn remux(input: &Path, out: &Path) -> Result<()> {
+    let pipeline = gst::Pipeline::new();
+    let src = gst::ElementFactory::make("filesrc")
+        .property("location", input.display().to_string())
+        .build()?;
+    let demux = gst::ElementFactory::make("qtdemux").build()?;
+    let mux = gst::ElementFactory::make("mp4mux").build()?;
+    let sink = gst::ElementFactory::make("filesink")
+        .property("location", out.display().to_string())
+        .build()?;
+    pipeline.add_many([&src, &demux, &mux, &sink])?;
+    src.link(&demux)?;
+    mux.link(&sink)?;
+
+    // Tracks only appear once the demuxer has read the header
+    let weak = pipeline.downgrade();
+    demux.connect_pad_added(move |_, pad| {
+        let Some(pipeline) = weak.upgrade() else {
+            return;
+        };
+        if let Err(e) = link_track(&pipeline, pad, &mux) {
+            error!("Dropping track {}: {:#}", pad.name(), e);
+        }
+    });
+
+    pipeline.set_state(gst::State::Playing)?;
+    let bus = pipeline.bus().context("Pipeline has no bus")?;
+    let msg = bus.timed_pop_filtered(
+        gst::ClockTime::NONE,
+        &[gst::MessageType::Eos, gst::MessageType::Error],
+    );
+    pipeline.set_state(gst::State::Null)?;
+
+    let Some(msg) = msg else {
+        bail!("Remux ended unexpectedly");
+    };
+    match msg.view() {
+        gst::MessageView::Error(err) => bail!("Remux failed: {}", err.error()),
+        _ => Ok(()),
+    }
+}
+
+/// Feed a demuxed track through its parser into the muxer.
+fn link_track(pipeline: &gst::Pipeline, pad: &gst::Pad, mux: &gst::Element) -> Result<()> {
+    let name = pad.name();
+    let parser = if name.starts_with("video") {
+        "h264parse"
+    } else if name.starts_with("audio") {
+        "aacparse"
+    } else {
+        bail!("unsupported track type");
+    };
+
+    let queue = gst::ElementFactory::make("queue").build()?;
+    let parser = gst::ElementFactory::make(parser).build()?;
+    pipeline.add_many([&queue, &parser])?;
+    gst::Element::link_many([&queue, &parser, mux])?;
+    for element in [&queue, &parser] {
+        element.sync_state_with_parent()?;
+    }
+    let sink = queue.static_pad("sink").context("queue has no sink pad")?;
+    pad.link(&sink)?;
+    Ok(())
+}
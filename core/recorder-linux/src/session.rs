@@ -0,0 +1,161 @@
+//! The recording in progress, or waiting to start, and how it ends.
+
+use anyhow::anyhow;
+use mandygif_capture::{CaptureBackend, CaptureConfig, CaptureError};
+use mandygif_protocol::{
+    cursor_sidecar_path, recording_lock_path, ErrorKind, RecorderEvent, StopReason,
+};
+use nix::sys::statvfs::statvfs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{error, info, warn};
+
+/// Free space at which a recording is stopped, leaving room to finalize it.
+const DISK_RESERVE_BYTES: u64 = 32 << 20;
+
+/// When a recording stops without being asked to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub max_duration_ms: Option<u64>,
+    pub max_bytes: Option<u64>,
+}
+
+/// The recording in progress and where it is written.
+pub struct Session {
+    pub backend: Box<dyn CaptureBackend>,
+    pub out: PathBuf,
+    pub limits: Limits,
+}
+
+impl Session {
+    /// Why the recording has to end now, if it does.
+    pub fn limit_reached(&self) -> Option<StopReason> {
+        if let Err(e) = self.backend.check() {
+            error!("Capture failed: {:#}", e);
+            return Some(StopReason::Error);
+        }
+        let duration_ms = self.backend.stats().duration_ms;
+        if self
+            .limits
+            .max_duration_ms
+            .is_some_and(|max| duration_ms >= max)
+        {
+            return Some(StopReason::TimeLimit);
+        }
+        // Backends that write no file have no size
+        let size = std::fs::metadata(&self.out).map_or(0, |m| m.len());
+        if self.limits.max_bytes.is_some_and(|max| size >= max) {
+            return Some(StopReason::SizeLimit);
+        }
+        if free_space(&self.out).is_some_and(|free| free < DISK_RESERVE_BYTES) {
+            return Some(StopReason::DiskFull);
+        }
+        None
+    }
+}
+
+/// A start request waiting out its delay.
+pub struct Pending {
+    pub backend: Box<dyn CaptureBackend>,
+    pub config: CaptureConfig,
+    pub limits: Limits,
+    pub at: Instant,
+}
+
+/// Start capturing and make it the active session.
+pub fn begin(
+    mut backend: Box<dyn CaptureBackend>,
+    config: CaptureConfig,
+    limits: Limits,
+    active: &mut Option<Session>,
+) -> RecorderEvent {
+    match backend.start(&config) {
+        Ok(()) => {
+            let bytes_per_sec = config.estimated_bytes_per_sec();
+            let free_bytes = free_space(&config.out);
+            if let Some(free) = free_bytes {
+                info!(
+                    "About {} MB/min, {} MB free",
+                    (bytes_per_sec * 60) >> 20,
+                    free >> 20
+                );
+            }
+            *active = Some(Session {
+                backend,
+                out: config.out,
+                limits,
+            });
+            RecorderEvent::Started {
+                pts_ms: 0,
+                bytes_per_sec,
+                free_bytes,
+            }
+        }
+        Err(e) => failure(CaptureError::kind_of(&e), &e),
+    }
+}
+
+pub fn stop(mut session: Session, reason: StopReason) -> RecorderEvent {
+    match session.backend.stop() {
+        Ok(stats) => {
+            if stats.dropped_frames > 0 {
+                warn!(
+                    "{} of {} frames dropped",
+                    stats.dropped_frames,
+                    stats.frames + stats.dropped_frames
+                );
+            }
+            RecorderEvent::Stopped {
+                duration_ms: stats.duration_ms,
+                path: session.out,
+                dropped_frames: stats.dropped_frames,
+                actual_fps: stats.actual_fps(),
+                reason,
+            }
+        }
+        Err(e) => failure(ErrorKind::IoError, &e),
+    }
+}
+
+/// Stop recording and delete everything it wrote.
+pub fn discard(mut session: Session) -> RecorderEvent {
+    // Stopping closes the file and releases the lock before they go
+    if let Err(e) = session.backend.stop() {
+        warn!("Recording did not stop cleanly: {:#}", e);
+    }
+    let leftovers = [
+        cursor_sidecar_path(&session.out),
+        recording_lock_path(&session.out),
+        session.out,
+    ];
+    for path in leftovers {
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                let e = anyhow!("Failed to remove {}: {e}", path.display());
+                return failure(ErrorKind::IoError, &e);
+            }
+        }
+    }
+    info!("Recording cancelled");
+    RecorderEvent::Cancelled
+}
+
+/// Bytes available to us on the filesystem holding `out`.
+fn free_space(out: &Path) -> Option<u64> {
+    let dir = out.parent().filter(|p| !p.as_os_str().is_empty())?;
+    let stat = statvfs(dir).ok()?;
+    // Field widths differ between targets
+    #[allow(clippy::useless_conversion)]
+    Some(u64::from(stat.blocks_available()) * u64::from(stat.fragment_size()))
+}
+
+pub fn failure(kind: ErrorKind, e: &anyhow::Error) -> RecorderEvent {
+    error!("Command failed: {:#}", e);
+    RecorderEvent::Error {
+        kind,
+        hint: e.to_string(),
+    }
+}
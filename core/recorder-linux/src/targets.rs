@@ -0,0 +1,156 @@
+//! Monitors and windows of the X11 session, for `ListTargets` and
+//! monitor/window capture targets.
+
+use anyhow::{bail, Context, Result};
+use mandygif_capture::even_size;
+use mandygif_protocol::{CaptureRegion, CaptureTarget, MonitorId, MonitorInfo, WindowInfo};
+use x11rb::connection::Connection;
+use x11rb::protocol::randr::ConnectionExt as _;
+use x11rb::protocol::xproto::{self, AtomEnum, ConnectionExt as _};
+use x11rb::rust_connection::RustConnection;
+
+/// Where to record once a `CaptureTarget` has been looked up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedTarget {
+    pub region: CaptureRegion,
+    /// Window to follow, if the target was one.
+    pub window: Option<u64>,
+}
+
+/// Turn `target` into a screen area (and window), using `region` for
+/// region targets.
+///
+/// # Errors
+/// Returns error if the X server cannot be reached or the monitor or
+/// window does not exist.
+pub fn resolve_target(target: &CaptureTarget, region: CaptureRegion) -> Result<ResolvedTarget> {
+    let (region, window) = match target {
+        CaptureTarget::Region => (region, None),
+        CaptureTarget::Monitor { monitor } => {
+            let found = list_monitors()?.into_iter().find(|m| match monitor {
+                MonitorId::Index(index) => m.index == *index,
+                MonitorId::Name(name) => m.name == *name,
+            });
+            let Some(found) = found else {
+                bail!("No monitor {:?}", monitor);
+            };
+            (found.region, None)
+        }
+        CaptureTarget::Window { xid } => {
+            let (conn, screen) = connect()?;
+            let root = conn.setup().roots[screen].root;
+            let window = u32::try_from(*xid).context("Not an X11 window id")?;
+            let region = window_region(&conn, root, window)
+                .with_context(|| format!("No window {xid:#x}"))?;
+            (region, Some(*xid))
+        }
+    };
+    Ok(ResolvedTarget {
+        region: even_size(&region),
+        window,
+    })
+}
+
+/// Active monitors, numbered in the order the X server reports them.
+///
+/// # Errors
+/// Returns error if the X server cannot be reached or lacks `RandR` 1.5.
+pub fn list_monitors() -> Result<Vec<MonitorInfo>> {
+    let (conn, screen) = connect()?;
+    let root = conn.setup().roots[screen].root;
+    let reply = conn
+        .randr_get_monitors(root, true)?
+        .reply()
+        .context("RandR 1.5 is required to list monitors")?;
+
+    let mut monitors = Vec::new();
+    for (index, monitor) in (0..).zip(reply.monitors) {
+        let name = conn.get_atom_name(monitor.name)?.reply()?.name;
+        monitors.push(MonitorInfo {
+            index,
+            name: String::from_utf8_lossy(&name).into_owned(),
+            region: CaptureRegion {
+                x: i32::from(monitor.x),
+                y: i32::from(monitor.y),
+                width: u32::from(monitor.width),
+                height: u32::from(monitor.height),
+            },
+            primary: monitor.primary,
+        });
+    }
+    Ok(monitors)
+}
+
+/// Top-level windows the window manager lists, in its stacking order.
+///
+/// Windows that vanish while being listed are skipped.
+///
+/// # Errors
+/// Returns error if the X server cannot be reached or the window manager
+/// does not publish `_NET_CLIENT_LIST`.
+pub fn list_windows() -> Result<Vec<WindowInfo>> {
+    let (conn, screen) = connect()?;
+    let root = conn.setup().roots[screen].root;
+    let client_list = atom(&conn, b"_NET_CLIENT_LIST")?;
+    let clients: Vec<u32> = conn
+        .get_property(false, root, client_list, AtomEnum::WINDOW, 0, u32::MAX)?
+        .reply()?
+        .value32()
+        .context("Window manager does not list its windows")?
+        .collect();
+
+    let mut windows = Vec::new();
+    for window in clients {
+        let (Ok(title), Ok(region)) = (title(&conn, window), window_region(&conn, root, window))
+        else {
+            continue;
+        };
+        windows.push(WindowInfo {
+            xid: u64::from(window),
+            title,
+            region,
+        });
+    }
+    Ok(windows)
+}
+
+fn connect() -> Result<(RustConnection, usize)> {
+    x11rb::connect(None).context("Failed to connect to X server")
+}
+
+fn atom(conn: &RustConnection, name: &[u8]) -> Result<xproto::Atom> {
+    Ok(conn.intern_atom(false, name)?.reply()?.atom)
+}
+
+/// `_NET_WM_NAME`, falling back to the legacy `WM_NAME`.
+fn title(conn: &RustConnection, window: xproto::Window) -> Result<String> {
+    let utf8 = atom(conn, b"UTF8_STRING")?;
+    let net_name = atom(conn, b"_NET_WM_NAME")?;
+    let mut name = conn
+        .get_property(false, window, net_name, utf8, 0, 1024)?
+        .reply()?
+        .value;
+    if name.is_empty() {
+        name = conn
+            .get_property(false, window, AtomEnum::WM_NAME, AtomEnum::ANY, 0, 1024)?
+            .reply()?
+            .value;
+    }
+    Ok(String::from_utf8_lossy(&name).into_owned())
+}
+
+/// Position of `window`'s contents on the root window, and its size.
+pub(crate) fn window_region(
+    conn: &RustConnection,
+    root: xproto::Window,
+    window: xproto::Window,
+) -> Result<CaptureRegion> {
+    let geometry = conn.get_geometry(window)?.reply()?;
+    let origin = conn.translate_coordinates(window, root, 0, 0)?.reply()?;
+    Ok(CaptureRegion {
+        x: i32::from(origin.dst_x),
+        y: i32::from(origin.dst_y),
+        width: u32::from(geometry.width),
+        height: u32::from(geometry.height),
+    })
+}
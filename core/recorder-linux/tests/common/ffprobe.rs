@@ -0,0 +1,70 @@
+//! File inspection with ffprobe.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// What ffprobe reports about a file's video.
+pub struct Probe {
+    pub width: u32,
+    pub height: u32,
+    pub frames: u64,
+    pub duration_ms: u64,
+}
+
+/// Size, frame count and duration of the first video stream in `path`.
+pub fn probe(path: &Path) -> Result<Probe> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0", "-count_frames"])
+        .args([
+            "-show_entries",
+            "stream=width,height,nb_read_frames:format=duration",
+            "-of",
+            "default=noprint_wrappers=1",
+        ])
+        .arg(path)
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let field = |key: &str| {
+        text.lines()
+            .find_map(|l| l.strip_prefix(key)?.strip_prefix('='))
+            .with_context(|| format!("ffprobe reported no {key}"))
+    };
+    let seconds: f64 = field("duration")?.parse()?;
+    Ok(Probe {
+        width: field("width")?.parse()?,
+        height: field("height")?.parse()?,
+        frames: field("nb_read_frames")?.parse()?,
+        duration_ms: (seconds * 1000.0).round() as u64,
+    })
+}
+
+/// Type and duration (ms) of every stream in the file.
+pub fn streams(path: &Path) -> Result<Vec<(String, u64)>> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-show_entries", "stream=codec_type,duration"])
+        .args(["-of", "csv=p=0"])
+        .arg(path)
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "ffprobe failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| {
+            let (kind, seconds) = line.split_once(',').context("bad ffprobe line")?;
+            let seconds: f64 = seconds.parse()?;
+            Ok((kind.to_string(), (seconds * 1000.0).round() as u64))
+        })
+        .collect()
+}
@@ -0,0 +1,141 @@
+//! Shared setup for the recorder integration tests.
+
+// Each test binary uses its own subset
+#![allow(dead_code)]
+
+pub mod ffprobe;
+
+use anyhow::{bail, Context, Result};
+use gstreamer as gst;
+use mandygif_capture::CaptureConfig;
+use mandygif_protocol::*;
+use mandygif_recorder_linux::Recorder;
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// The parts of a `Start` command tests vary; the rest is a silent,
+/// draft-quality 320x240 region at 30 fps.
+pub struct StartOptions {
+    pub backend: CaptureBackendKind,
+    pub quality: RecordingQuality,
+    pub delay_ms: u64,
+    pub max_duration_ms: Option<u64>,
+    pub max_bytes: Option<u64>,
+    pub out: PathBuf,
+}
+
+impl Default for StartOptions {
+    fn default() -> Self {
+        Self {
+            backend: CaptureBackendKind::Synthetic,
+            quality: RecordingQuality::Draft,
+            delay_ms: 0,
+            max_duration_ms: None,
+            max_bytes: None,
+            out: PathBuf::from("/tmp/never-written.mp4"),
+        }
+    }
+}
+
+impl StartOptions {
+    pub fn command(self) -> RecorderCommand {
+        RecorderCommand::Start {
+            region: CaptureRegion {
+                x: 0,
+                y: 0,
+                width: 320,
+                height: 240,
+            },
+            target: CaptureTarget::Region,
+            fps: 30,
+            cursor: false,
+            backend: self.backend,
+            audio: AudioOptions::default(),
+            quality: self.quality,
+            frame_rate: FrameRateMode::Constant,
+            delay_ms: self.delay_ms,
+            max_duration_ms: self.max_duration_ms,
+            max_bytes: self.max_bytes,
+            out: self.out,
+        }
+    }
+}
+
+/// Fail unless GStreamer has every element in `names`.
+///
+/// Tests that record for real are `#[ignore]`d and run with `--ignored`;
+/// once asked for, a missing plugin is a failure rather than a skip.
+pub fn require_elements(names: &[&str]) -> Result<()> {
+    Recorder::init()?;
+    let missing: Vec<_> = names
+        .iter()
+        .filter(|name| gst::ElementFactory::find(name).is_none())
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        bail!("Missing GStreamer elements: {}", missing.join(", "));
+    }
+    Ok(())
+}
+
+/// Silent draft-quality recording of a `width`x`height` area at `fps`.
+pub fn capture_config(out: &Path, width: u32, height: u32, fps: u32) -> CaptureConfig {
+    CaptureConfig {
+        region: CaptureRegion {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        },
+        window: None,
+        fps,
+        cursor: false,
+        audio: AudioOptions::default(),
+        quality: RecordingQuality::Draft,
+        frame_rate: FrameRateMode::Constant,
+        out: out.to_path_buf(),
+    }
+}
+
+/// The `recorder-linux` binary, driven over its stdin and stdout.
+pub struct RecorderProcess {
+    pub child: Child,
+    stdin: Option<ChildStdin>,
+    events: Lines<BufReader<ChildStdout>>,
+}
+
+impl RecorderProcess {
+    pub fn spawn() -> Result<Self> {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_recorder-linux"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().context("no stdin")?;
+        let events = BufReader::new(child.stdout.take().context("no stdout")?).lines();
+        Ok(Self {
+            child,
+            stdin: Some(stdin),
+            events,
+        })
+    }
+
+    pub fn send(&mut self, cmd: &RecorderCommand) -> Result<()> {
+        let stdin = self.stdin.as_mut().context("stdin closed")?;
+        stdin.write_all(to_jsonl(cmd)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn next_event(&mut self) -> Result<RecorderEvent> {
+        let line = self.events.next().context("recorder exited")??;
+        Ok(parse_recorder_event(&line)?)
+    }
+
+    /// Close stdin, which ends the recorder, and wait for it to exit.
+    pub fn finish(mut self) -> Result<()> {
+        drop(self.stdin.take());
+        self.child.wait()?;
+        Ok(())
+    }
+}
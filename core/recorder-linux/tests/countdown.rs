@@ -0,0 +1,126 @@
+//! Delayed starts and cancelling through the recorder binary, on the synthetic backend.
+
+mod common;
+
+use anyhow::Result;
+use common::{RecorderProcess, StartOptions};
+use mandygif_protocol::*;
+
+fn start(delay_ms: u64) -> RecorderCommand {
+    StartOptions {
+        delay_ms,
+        ..StartOptions::default()
+    }
+    .command()
+}
+
+#[test]
+fn test_countdown_then_start() -> Result<()> {
+    let mut recorder = RecorderProcess::spawn()?;
+
+    recorder.send(&start(300))?;
+    assert_eq!(
+        recorder.next_event()?,
+        RecorderEvent::Countdown { remaining_ms: 300 }
+    );
+
+    // Ticks count down until capture begins
+    let mut last = 300;
+    let started = loop {
+        match recorder.next_event()? {
+            RecorderEvent::Countdown { remaining_ms } => {
+                assert!(remaining_ms <= last, "countdown went up");
+                last = remaining_ms;
+            }
+            event => break event,
+        }
+    };
+    assert!(matches!(started, RecorderEvent::Started { .. }));
+
+    recorder.send(&RecorderCommand::Stop)?;
+    while !matches!(recorder.next_event()?, RecorderEvent::Stopped { .. }) {}
+    recorder.finish()
+}
+
+#[test]
+fn test_stop_cancels_countdown() -> Result<()> {
+    let mut recorder = RecorderProcess::spawn()?;
+
+    recorder.send(&start(60_000))?;
+    assert!(matches!(
+        recorder.next_event()?,
+        RecorderEvent::Countdown { .. }
+    ));
+    // A second start is refused while the first is pending
+    recorder.send(&start(0))?;
+    recorder.send(&RecorderCommand::Stop)?;
+
+    let mut rest = Vec::new();
+    loop {
+        match recorder.next_event()? {
+            RecorderEvent::Countdown { .. } => {}
+            RecorderEvent::Cancelled => break,
+            event => rest.push(event),
+        }
+    }
+    assert!(matches!(
+        rest.as_slice(),
+        [RecorderEvent::Error {
+            kind: ErrorKind::InvalidInput,
+            ..
+        }]
+    ));
+
+    // Nothing is left running to stop
+    recorder.send(&RecorderCommand::Stop)?;
+    assert!(matches!(
+        recorder.next_event()?,
+        RecorderEvent::Error { .. }
+    ));
+    recorder.finish()
+}
+
+#[test]
+fn test_cancel_discards_recording() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let out = dir.path().join("clip.mp4");
+    let mut recorder = RecorderProcess::spawn()?;
+
+    recorder.send(
+        &StartOptions {
+            out: out.clone(),
+            ..StartOptions::default()
+        }
+        .command(),
+    )?;
+    assert!(matches!(
+        recorder.next_event()?,
+        RecorderEvent::Started { .. }
+    ));
+    // The synthetic backend writes nothing, so stand in for what a real one would
+    std::fs::write(&out, b"partial")?;
+    std::fs::write(cursor_sidecar_path(&out), b"{}")?;
+
+    // Cancelling works while paused too
+    recorder.send(&RecorderCommand::Pause)?;
+    recorder.send(&RecorderCommand::Cancel)?;
+    loop {
+        match recorder.next_event()? {
+            RecorderEvent::Progress { .. } | RecorderEvent::Paused { .. } => {}
+            event => {
+                assert_eq!(event, RecorderEvent::Cancelled);
+                break;
+            }
+        }
+    }
+    assert!(!out.exists());
+    assert!(!cursor_sidecar_path(&out).exists());
+
+    // Nothing is left running to stop
+    recorder.send(&RecorderCommand::Stop)?;
+    assert!(matches!(
+        recorder.next_event()?,
+        RecorderEvent::Error { .. }
+    ));
+    recorder.finish()
+}
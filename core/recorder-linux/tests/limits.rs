@@ -0,0 +1,41 @@
+//! Recordings that stop on their own, through the recorder binary.
+
+mod common;
+
+use anyhow::Result;
+use common::{RecorderProcess, StartOptions};
+use mandygif_protocol::*;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_time_limit_stops_recording() -> Result<()> {
+    let mut recorder = RecorderProcess::spawn()?;
+    recorder.send(
+        &StartOptions {
+            max_duration_ms: Some(500),
+            max_bytes: Some(u64::MAX),
+            ..StartOptions::default()
+        }
+        .command(),
+    )?;
+    let began = Instant::now();
+
+    // No stop command is sent
+    let (duration_ms, reason) = loop {
+        if let RecorderEvent::Stopped {
+            duration_ms,
+            reason,
+            ..
+        } = recorder.next_event()?
+        {
+            break (duration_ms, reason);
+        }
+    };
+    assert_eq!(reason, StopReason::TimeLimit);
+    assert!(
+        (500..700).contains(&duration_ms),
+        "stopped at {duration_ms} ms"
+    );
+    assert!(began.elapsed() < Duration::from_secs(5));
+    recorder.finish()
+}
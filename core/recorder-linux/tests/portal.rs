@@ -0,0 +1,91 @@
+//! Wayland backend behaviour against a mock screen cast portal.
+
+mod common;
+
+use anyhow::Result;
+use common::capture_config;
+use mandygif_capture::{CaptureBackend, CaptureConfig, CaptureError};
+use mandygif_protocol::{ErrorKind, FrameRateMode};
+use mandygif_recorder_linux::{GstBackend, GstSource, PortalGrant, PortalStream, ScreenCastPortal};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Portal that answers immediately instead of showing a dialog, sharing
+/// one 1280x720 (logical) monitor at `scale`.
+struct MockPortal {
+    allow: bool,
+    scale: f64,
+    asked_for_cursor: Arc<AtomicBool>,
+}
+
+impl ScreenCastPortal for MockPortal {
+    fn open(&self, cursor: bool) -> Result<PortalGrant> {
+        self.asked_for_cursor.store(cursor, Ordering::Relaxed);
+        if !self.allow {
+            return Err(CaptureError::PermissionDenied("declined in test".into()).into());
+        }
+        Ok(PortalGrant::new(
+            vec![PortalStream {
+                node_id: 7,
+                position: (0, 0),
+                size: (1280, 720),
+                scale: self.scale,
+            }],
+            None,
+        ))
+    }
+}
+
+fn config(x: i32) -> CaptureConfig {
+    let mut config = capture_config(Path::new("/tmp/never-written.mp4"), 640, 480, 30);
+    config.region.x = x;
+    config.cursor = true;
+    config
+}
+
+fn backend(allow: bool) -> (GstBackend, Arc<AtomicBool>) {
+    let asked = Arc::new(AtomicBool::new(false));
+    let portal = MockPortal {
+        allow,
+        scale: 1.0,
+        asked_for_cursor: Arc::clone(&asked),
+    };
+    let backend = GstBackend::new(GstSource::Wayland).with_portal(Box::new(portal));
+    (backend, asked)
+}
+
+#[test]
+fn test_denial_maps_to_permission_denied() {
+    let (mut backend, asked) = backend(false);
+    let err = backend.start(&config(0)).expect_err("portal denied");
+
+    assert_eq!(CaptureError::kind_of(&err), ErrorKind::PermissionDenied);
+    assert!(
+        asked.load(Ordering::Relaxed),
+        "cursor preference is forwarded"
+    );
+}
+
+#[test]
+fn test_region_outside_shared_screen_fails() {
+    let (mut backend, _) = backend(true);
+    let err = backend.start(&config(1000)).expect_err("region off-screen");
+
+    assert_eq!(CaptureError::kind_of(&err), ErrorKind::IoError);
+}
+
+#[test]
+fn test_region_is_found_on_a_scaled_monitor() -> Result<()> {
+    let portal = MockPortal {
+        allow: true,
+        scale: 2.0,
+        asked_for_cursor: Arc::default(),
+    };
+    let grant = portal.open(false)?;
+
+    // Off the monitor at 1x, but a 2x monitor is 2560x1440 physical pixels
+    let source = grant.source(&config(1000).region, 30, FrameRateMode::Constant)?;
+    assert!(source.contains("videocrop left=1000 top=0 right=920 bottom=960"));
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! Kill the recorder binary mid-recording and recover what it wrote.
+//!
+//! Needs the GStreamer base/good/ugly plugins, but no display; run with
+//! `cargo test -- --ignored`.
+
+mod common;
+
+use anyhow::Result;
+use common::{require_elements, RecorderProcess, StartOptions};
+use mandygif_capture::{mp4_duration_ms, RecordingLock};
+use mandygif_protocol::*;
+use mandygif_recorder_linux::{recover, recovered_path};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+#[ignore = "needs GStreamer plugins"]
+fn test_killed_recording_is_recovered() -> Result<()> {
+    require_elements(&["videotestsrc", "x264enc", "qtmux", "qtdemux", "mp4mux"])?;
+
+    let dir = tempfile::tempdir()?;
+    let out = dir.path().join("crash.mp4");
+    let mut recorder = RecorderProcess::spawn()?;
+    recorder.send(
+        &StartOptions {
+            backend: CaptureBackendKind::TestSource,
+            quality: RecordingQuality::High,
+            out: out.clone(),
+            ..StartOptions::default()
+        }
+        .command(),
+    )?;
+    assert!(matches!(
+        recorder.next_event()?,
+        RecorderEvent::Started { .. }
+    ));
+
+    thread::sleep(Duration::from_millis(3500));
+    recorder.child.kill()?;
+    recorder.child.wait()?;
+
+    assert!(
+        RecordingLock::is_stale(&out),
+        "crash leaves the lock behind"
+    );
+    // What an earlier crash left is replaced, not kept alongside
+    std::fs::write(recovered_path(&out), b"older recovery")?;
+    let (recovered, duration_ms) = recover(&out)?;
+    assert_eq!(recovered, dir.path().join("crash.recovered.mp4"));
+    let recoveries = std::fs::read_dir(dir.path())?
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .is_ok_and(|e| e.file_name().to_string_lossy().contains("recovered"))
+        })
+        .count();
+    assert_eq!(recoveries, 1);
+
+    // Fragments are a second long, so at most the last one is lost
+    assert!(
+        (2000..=3600).contains(&duration_ms),
+        "recovered {duration_ms} ms"
+    );
+    assert_eq!(mp4_duration_ms(&recovered)?, duration_ms);
+    // A new recording can take the original path
+    assert_ne!(recovered, out);
+    assert!(!out.exists());
+    assert!(!recording_lock_path(&out).exists());
+    assert!(recover(&out).is_err(), "nothing left to recover");
+    Ok(())
+}
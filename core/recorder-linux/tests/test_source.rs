@@ -0,0 +1,191 @@
+//! Record the `videotestsrc` backend end to end and inspect the MP4.
+//!
+//! Needs the GStreamer base/good/ugly plugins and ffprobe, but no display;
+//! run with `cargo test -- --ignored`.
+
+mod common;
+
+use anyhow::{bail, Context, Result};
+use common::ffprobe::{probe, streams, Probe};
+use common::{capture_config, require_elements};
+use gstreamer as gst;
+use mandygif_capture::{CaptureBackend, CaptureConfig, CaptureStats};
+use mandygif_protocol::{
+    recording_lock_path, AudioOptions, AudioSource, CaptureRegion, FrameRateMode, RecordingQuality,
+};
+use mandygif_recorder_linux::{GstBackend, GstSource};
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Live sources start and stop on wall-clock time, so allow a little slack.
+const FRAME_SLACK: u64 = 3;
+
+fn require_tools() -> Result<()> {
+    require_elements(&["videotestsrc", "timeoverlay", "x264enc", "qtmux"])?;
+    Command::new("ffprobe")
+        .arg("-version")
+        .output()
+        .context("ffprobe is required")?;
+    Ok(())
+}
+
+fn record(width: u32, height: u32, fps: u32, seconds: u64) -> Result<(Probe, CaptureStats)> {
+    let dir = tempfile::tempdir()?;
+    let out = dir.path().join("pattern.mp4");
+    let stats = record_to(&capture_config(&out, width, height, fps), seconds)?;
+    Ok((probe(&out)?, stats))
+}
+
+fn record_to(config: &CaptureConfig, seconds: u64) -> Result<CaptureStats> {
+    let mut backend = GstBackend::new(GstSource::TestPattern);
+    backend.start(config)?;
+    thread::sleep(Duration::from_secs(seconds));
+    let stats = backend.stop()?;
+
+    assert!(
+        !recording_lock_path(&config.out).exists(),
+        "a finalized recording needs no recovery"
+    );
+    Ok(stats)
+}
+
+#[test]
+#[ignore = "needs GStreamer plugins and ffprobe"]
+fn test_records_region_size_and_rate() -> Result<()> {
+    require_tools()?;
+
+    for (width, height, fps, seconds) in [(320, 240, 30, 2), (200, 100, 10, 1)] {
+        let (p, stats) = record(width, height, fps, seconds)?;
+        let expected = u64::from(fps) * seconds;
+
+        assert_eq!((p.width, p.height), (width, height));
+        assert!(
+            p.frames.abs_diff(expected) <= FRAME_SLACK,
+            "{} frames, expected about {expected}",
+            p.frames
+        );
+        assert!(
+            p.duration_ms.abs_diff(seconds * 1000) <= FRAME_SLACK * 1000 / u64::from(fps),
+            "{} ms, expected about {} ms",
+            p.duration_ms,
+            seconds * 1000
+        );
+
+        // Reported stats come from the pipeline and file, not the wall clock
+        assert!(
+            stats.duration_ms.abs_diff(p.duration_ms) <= 1000 / u64::from(fps),
+            "reported {} ms, file has {} ms",
+            stats.duration_ms,
+            p.duration_ms
+        );
+        assert!(stats.frames.abs_diff(p.frames) <= 1);
+    }
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs GStreamer plugins and ffprobe"]
+fn test_audio_tracks_stay_in_sync() -> Result<()> {
+    require_tools()?;
+    require_elements(&["audiotestsrc", "aacparse"])?;
+    let aac = ["fdkaacenc", "avenc_aac", "voaacenc"];
+    if !aac
+        .iter()
+        .any(|name| gst::ElementFactory::find(name).is_some())
+    {
+        bail!("Missing an AAC encoder: one of {}", aac.join(", "));
+    }
+
+    let dir = tempfile::tempdir()?;
+    let out = dir.path().join("tones.mp4");
+    let audio = AudioOptions {
+        system: Some(AudioSource::default()),
+        microphone: Some(AudioSource {
+            device: None,
+            gain: 0.5,
+        }),
+    };
+    let config = CaptureConfig {
+        audio,
+        ..capture_config(&out, 320, 240, 30)
+    };
+    record_to(&config, 2)?;
+
+    let streams = streams(&out)?;
+    let video = streams
+        .iter()
+        .find(|(kind, _)| kind == "video")
+        .map(|(_, ms)| *ms)
+        .context("no video stream")?;
+    let audio: Vec<u64> = streams
+        .iter()
+        .filter(|(kind, _)| kind == "audio")
+        .map(|(_, ms)| *ms)
+        .collect();
+
+    assert_eq!(audio.len(), 2, "one track per source");
+    for ms in audio {
+        // Within a few frames plus one AAC frame of the video
+        assert!(ms.abs_diff(video) <= 150, "audio {ms} ms, video {video} ms");
+    }
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs GStreamer plugins and ffprobe"]
+fn test_lossless_keeps_full_chroma() -> Result<()> {
+    require_tools()?;
+
+    let dir = tempfile::tempdir()?;
+    let out = dir.path().join("lossless.mp4");
+    let config = CaptureConfig {
+        quality: RecordingQuality::Lossless,
+        ..capture_config(&out, 320, 240, 30)
+    };
+    record_to(&config, 1)?;
+
+    let output = Command::new("ffprobe")
+        .args(["-v", "error", "-select_streams", "v:0"])
+        .args(["-show_entries", "stream=pix_fmt", "-of", "csv=p=0"])
+        .arg(&out)
+        .output()?;
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "yuv444p");
+    Ok(())
+}
+
+#[test]
+#[ignore = "needs GStreamer plugins and ffprobe"]
+fn test_variable_rate_skips_static_frames() -> Result<()> {
+    require_tools()?;
+
+    let dir = tempfile::tempdir()?;
+    let out = dir.path().join("static.mp4");
+    let config = CaptureConfig {
+        frame_rate: FrameRateMode::Variable { max_gap_ms: 500 },
+        ..capture_config(&out, 320, 240, 30)
+    };
+    let stats = record_to(&config, 2)?;
+    let p = probe(&out)?;
+
+    // The pattern never changes, so only the forced repeats are recorded
+    assert!(p.frames <= 8, "{} frames of a static pattern", p.frames);
+    assert_eq!(stats.dropped_frames, 0);
+    assert!(
+        p.duration_ms.abs_diff(2000) <= 200,
+        "{} ms, expected about 2000 ms",
+        p.duration_ms
+    );
+    Ok(())
+}
+
+#[test]
+fn test_rejects_empty_region() {
+    let mut backend = GstBackend::new(GstSource::TestPattern);
+    let config = CaptureConfig {
+        region: CaptureRegion::default(),
+        ..capture_config(Path::new("/tmp/never-written.mp4"), 320, 240, 30)
+    };
+    assert!(backend.start(&config).is_err());
+}
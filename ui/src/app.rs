@@ -1,12 +1,42 @@
 #![allow(non_snake_case)]
 
+use crate::chrome::OverlayChrome;
 use crate::components::control_bar::ControlBar;
+use crate::components::export_panel::ExportPanel;
 use crate::components::resize_handle::ResizeHandles;
-use crate::hooks::use_recorder;
+use crate::components::settings_panel::SettingsPanel;
+use crate::hooks::export::use_export;
+use crate::hooks::hotkeys::use_hotkeys;
+use crate::hooks::recorder::use_recorder;
+use crate::hooks::region::use_region;
+use crate::hooks::settings::use_settings;
+use crate::snap::adjust;
 use crate::state::{AppMode, AppState};
-use dioxus::desktop::tao::dpi::LogicalSize;
 use dioxus::desktop::use_window;
 use dioxus::prelude::*;
+use mandygif_protocol::CaptureTarget;
+
+/// Arrow keys move the region by 1 px, or by 10 with Shift. With Alt they
+/// resize it by 2 px (10 with Shift), keeping the even size H.264 needs.
+///
+/// Returns the change as (dx, dy, dw, dh).
+fn arrow_step(evt: &KeyboardEvent) -> Option<(i32, i32, i32, i32)> {
+    let modifiers = evt.modifiers();
+    let resize = modifiers.alt();
+    let step = match (modifiers.shift(), resize) {
+        (true, _) => 10,
+        (false, true) => 2,
+        (false, false) => 1,
+    };
+    let (x, y) = match evt.key() {
+        Key::ArrowLeft => (-step, 0),
+        Key::ArrowRight => (step, 0),
+        Key::ArrowUp => (0, -step),
+        Key::ArrowDown => (0, step),
+        _ => return None,
+    };
+    Some(if resize { (0, 0, x, y) } else { (x, y, 0, 0) })
+}
 
 #[allow(dependency_on_unit_never_type_fallback)]
 pub fn App() -> Element {
@@ -14,11 +44,18 @@ pub fn App() -> Element {
     let state = use_context::<AppState>();
     let window = use_window();
     let recorder = use_recorder();
+    let export = use_export();
+    use_hotkeys(&recorder);
+    let region_ctl = use_region();
 
-    // Initial size setup
-    let win_startup = window.clone();
-    use_hook(move || {
-        win_startup.set_inner_size(LogicalSize::new(800.0, 600.0));
+    // Restores last session's region and settings
+    let reset_settings = use_settings(&region_ctl);
+    let mut show_settings = use_signal(|| false);
+    // Shortcuts that stop working open the settings, which can be closed again
+    use_effect(move || {
+        if !state.hotkey_errors.read().is_empty() {
+            show_settings.set(true);
+        }
     });
 
     let current_mode = *state.mode.read();
@@ -26,16 +63,46 @@ pub fn App() -> Element {
 
     // Class determines border color & background tint
     let state_class = match current_mode {
+        AppMode::Countdown => "state-countdown",
         AppMode::Recording => "state-recording",
         AppMode::Review | AppMode::Exporting => "state-review",
         AppMode::Idle => "state-idle",
     };
 
+    // The stylesheet lays out the chrome from the margins the recorder skips
+    let chrome_vars = OverlayChrome::OVERLAY.css_vars();
+
     rsx! {
         style { dangerous_inner_html: include_str!("style.css") }
+        style { dangerous_inner_html: include_str!("panel.css") }
 
         div {
             class: "app-frame {state_class}",
+            style: "{chrome_vars}",
+            // Focusable so Esc and the arrow keys reach us before anything
+            // has been clicked
+            tabindex: "0",
+            onmounted: move |evt| async move {
+                let _ = evt.set_focus(true).await;
+            },
+            onkeydown: move |evt| {
+                if evt.key() == Key::Escape {
+                    recorder.cancel.call(());
+                } else if let Some((dx, dy, dw, dh)) = arrow_step(&evt) {
+                    if *state.mode.peek() == AppMode::Idle {
+                        evt.prevent_default();
+                        let current = state.region.peek().clone();
+                        let adjusted = adjust(&current, dx, dy, dw, dh);
+                        region_ctl.place.call(state.region_preset.peek().fit(&adjusted, &current));
+                    }
+                }
+            },
+
+            // 0. Outline of exactly what gets recorded (a monitor or window
+            // target ignores the overlay)
+            if *state.capture_target.read() == CaptureTarget::Region {
+                div { class: "capture-area" }
+            }
 
             // 1. Resize Handles (Only interactive in Idle)
             if current_mode == AppMode::Idle {
@@ -49,11 +116,29 @@ pub fn App() -> Element {
                 span { class: "drag-bar-label", "MandyGIF" }
             }
 
-            // 3. Floating Control Bar
+            // Size of the region being resized, in recorded pixels
+            {state.resize_readout.read().clone().map(|region| rsx! {
+                div { class: "dimension-readout", "{region.width} × {region.height}" }
+            })}
+
+            // 3. Export or recording settings, above the control bar
+            if current_mode == AppMode::Review {
+                ExportPanel {}
+            } else if current_mode == AppMode::Idle && show_settings() {
+                SettingsPanel {}
+            }
+
+            // 4. Floating Control Bar
             ControlBar {
                 on_record: recorder.start,
+                on_pause: recorder.toggle_pause,
                 on_stop: recorder.stop,
-                on_export: recorder.export
+                on_cancel: recorder.cancel,
+                on_preset: region_ctl.pick_preset,
+                on_region: region_ctl.place,
+                on_reset: reset_settings,
+                on_settings: move |()| show_settings.toggle(),
+                on_export: export
             }
         }
     }
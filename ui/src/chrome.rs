@@ -0,0 +1,198 @@
+//! The overlay window's own UI, which must never end up in a recording.
+
+use mandygif_capture::{even_size, on_monitors};
+use mandygif_protocol::CaptureRegion;
+
+/// Width of the coloured frame drawn inside the window edge, in CSS pixels.
+pub const FRAME_WIDTH: u32 = 4;
+
+/// Width of the outline drawn just outside the capture region.
+pub const OUTLINE_WIDTH: u32 = 1;
+
+/// Height of the control pill, centred in the bottom strip.
+pub const PILL_HEIGHT: u32 = 52;
+
+/// Margins the overlay window keeps for its own UI.
+///
+/// The border runs along every edge, the drag bar along the top and the
+/// control pill sits in a strip along the bottom. The capture region is
+/// what lies inside all of them, so none of it is ever recorded; the
+/// stylesheet lays itself out from the same numbers (see `css_vars`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverlayChrome {
+    pub border: u32,
+    pub top: u32,
+    pub bottom: u32,
+}
+
+impl OverlayChrome {
+    /// Layout of the `MandyGIF` overlay window, in logical (CSS) pixels.
+    pub const OVERLAY: Self = Self {
+        border: 6,
+        top: 32,
+        bottom: 72,
+    };
+
+    /// The margins in physical pixels on a display with `scale` factor,
+    /// rounded outwards so fractional scales never expose chrome.
+    #[must_use]
+    pub fn scaled(self, scale: f64) -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let px = |logical: u32| (f64::from(logical) * scale.max(0.0)).ceil() as u32;
+        Self {
+            border: px(self.border),
+            top: px(self.top),
+            bottom: px(self.bottom),
+        }
+    }
+
+    /// Custom properties for the stylesheet, which lays out the chrome
+    /// from them.
+    #[must_use]
+    pub fn css_vars(self) -> String {
+        format!(
+            "--chrome-border: {}px; --chrome-top: {}px; --chrome-bottom: {}px; \
+             --frame-width: {FRAME_WIDTH}px; --outline-width: {OUTLINE_WIDTH}px; \
+             --pill-height: {PILL_HEIGHT}px;",
+            self.border, self.top, self.bottom
+        )
+    }
+
+    /// The screen area a window at `window` frames without covering it,
+    /// limited to what is on `monitors` (unless there are none) and
+    /// trimmed to the even dimensions H.264 needs.
+    ///
+    /// Everything is in physical pixels, so the margins must be `scaled`.
+    #[must_use]
+    pub fn capture_region(
+        &self,
+        window: &CaptureRegion,
+        monitors: &[CaptureRegion],
+    ) -> CaptureRegion {
+        let content = CaptureRegion {
+            x: window.x.saturating_add_unsigned(self.border),
+            y: window.y.saturating_add_unsigned(self.top),
+            width: window.width.saturating_sub(2 * self.border),
+            height: window.height.saturating_sub(self.top + self.bottom),
+        };
+        if monitors.is_empty() {
+            even_size(&content)
+        } else {
+            even_size(&on_monitors(&content, monitors))
+        }
+    }
+
+    /// The overlay window whose capture region is `region`, the inverse of
+    /// `capture_region` for even-sized regions on screen.
+    #[must_use]
+    pub fn window_around(&self, region: &CaptureRegion) -> CaptureRegion {
+        CaptureRegion {
+            x: region.x.saturating_sub_unsigned(self.border),
+            y: region.y.saturating_sub_unsigned(self.top),
+            width: region.width + 2 * self.border,
+            height: region.height + self.top + self.bottom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_controls_fit_in_the_chrome() {
+        let chrome = OverlayChrome::OVERLAY;
+        // The frame and the outline around the region share the border
+        assert!(FRAME_WIDTH + OUTLINE_WIDTH <= chrome.border);
+        assert!(PILL_HEIGHT <= chrome.bottom);
+
+        // Every margin reaches the stylesheet, which uses each of them
+        let style = [include_str!("style.css"), include_str!("panel.css")].concat();
+        for var in chrome
+            .css_vars()
+            .split(';')
+            .filter_map(|v| v.split(':').next())
+        {
+            let var = var.trim();
+            if !var.is_empty() {
+                assert!(style.contains(&format!("var({var})")), "{var} unused");
+            }
+        }
+    }
+
+    #[test]
+    fn test_region_sits_inside_the_chrome() {
+        let chrome = OverlayChrome::OVERLAY;
+        let window = CaptureRegion {
+            x: -400,
+            y: 100,
+            width: 801,
+            height: 601,
+        };
+
+        let region = chrome.capture_region(&window, &[]);
+        assert_eq!(
+            region,
+            CaptureRegion {
+                x: -394,
+                y: 132,
+                width: 788,
+                height: 496,
+            }
+        );
+        let around = chrome.window_around(&region);
+        assert_eq!(chrome.capture_region(&around, &[]), region);
+
+        // Too small to leave anything to record
+        let tiny = CaptureRegion {
+            width: 10,
+            height: 50,
+            ..window
+        };
+        let region = chrome.capture_region(&tiny, &[]);
+        assert_eq!((region.width, region.height), (0, 0));
+    }
+
+    #[test]
+    fn test_fractional_scales_round_outwards() {
+        let chrome = OverlayChrome::OVERLAY;
+        assert_eq!(chrome.scaled(1.0), chrome);
+        assert_eq!(
+            chrome.scaled(1.25),
+            OverlayChrome {
+                border: 8,
+                top: 40,
+                bottom: 90,
+            }
+        );
+
+        // An 800x600 logical overlay at 2x, half off the left of a monitor
+        // that sits left of the primary
+        let chrome = chrome.scaled(2.0);
+        let window = CaptureRegion {
+            x: -2800,
+            y: 200,
+            width: 1600,
+            height: 1200,
+        };
+        let screen = CaptureRegion {
+            x: -2560,
+            y: 0,
+            width: 2560,
+            height: 1440,
+        };
+        let region = chrome.capture_region(&window, &[screen]);
+        assert_eq!(
+            region,
+            CaptureRegion {
+                x: -2560,
+                y: 264,
+                width: 1348,
+                height: 992,
+            }
+        );
+        // The pill strip stays below the region
+        let bottom = i64::from(region.y) + i64::from(region.height);
+        assert!(bottom <= i64::from(window.y) + 1200 - i64::from(chrome.bottom));
+    }
+}
@@ -1,4 +1,13 @@
 // warden:ignore
+pub mod capture_setup;
 pub mod control_bar;
+pub mod export_estimate;
+pub mod export_panel;
+pub mod hotkey_fields;
 pub mod icons;
+pub mod limit_fields;
+pub mod region_fields;
 pub mod resize_handle;
+pub mod settings_panel;
+pub mod target_select;
+pub mod watermark_fields;
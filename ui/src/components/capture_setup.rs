@@ -0,0 +1,53 @@
+use super::target_select::TargetSelect;
+use crate::preset::RegionPreset;
+use crate::state::{use_app_state, COUNTDOWN_CHOICES};
+use dioxus::prelude::*;
+
+/// What to record, the shape to hold the region to and the countdown
+/// before recording starts. Shown in Idle mode.
+#[component]
+pub fn CaptureSetup(on_preset: EventHandler<RegionPreset>) -> Element {
+    let mut state = use_app_state();
+
+    let countdown = *state.countdown_secs.read();
+    let next_countdown = COUNTDOWN_CHOICES
+        .iter()
+        .copied()
+        .find(|&c| c > countdown)
+        .unwrap_or(COUNTDOWN_CHOICES[0]);
+
+    let preset = *state.region_preset.read();
+    let preset_index = RegionPreset::ALL
+        .iter()
+        .position(|&p| p == preset)
+        .unwrap_or(0);
+    let presets = RegionPreset::ALL.map(RegionPreset::label);
+
+    rsx! {
+        TargetSelect {}
+        select {
+            class: "target-select",
+            title: "Region shape",
+            value: "{preset_index}",
+            onchange: move |evt| {
+                if let Some(&preset) = evt
+                    .value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|i| RegionPreset::ALL.get(i))
+                {
+                    on_preset.call(preset);
+                }
+            },
+            for (i, label) in presets.iter().enumerate() {
+                option { key: "{i}", value: "{i}", selected: i == preset_index, "{label}" }
+            }
+        }
+        button {
+            class: "icon-btn countdown-btn",
+            title: "Countdown",
+            onclick: move |_| state.countdown_secs.set(next_countdown),
+            "{countdown}s"
+        }
+    }
+}
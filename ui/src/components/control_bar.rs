@@ -1,28 +1,76 @@
-// warden:ignore
-use crate::components::icons::{IconExport, IconStop};
+use super::capture_setup::CaptureSetup;
+use super::icons::{IconDiscard, IconExport, IconPause, IconReset, IconResume, IconStop};
+use super::region_fields::RegionFields;
+use crate::preset::RegionPreset;
+use crate::settings::Settings;
 use crate::state::{use_app_state, AppMode};
 use dioxus::prelude::*;
+use mandygif_protocol::CaptureRegion;
 
-#[component]
-pub fn ControlBar(
+/// What the control bar's buttons and fields do.
+#[derive(Props, Clone, PartialEq)]
+pub struct ControlActions {
     on_record: EventHandler<()>,
+    on_pause: EventHandler<()>,
     on_stop: EventHandler<()>,
+    on_cancel: EventHandler<()>,
+    on_preset: EventHandler<RegionPreset>,
+    on_region: EventHandler<CaptureRegion>,
+    on_reset: EventHandler<()>,
+    on_settings: EventHandler<()>,
     on_export: EventHandler<()>,
+}
+
+/// The control pill along the bottom of the overlay, with what can be
+/// done in the current mode.
+#[component]
+pub fn ControlBar(
+    ControlActions {
+        on_record,
+        on_pause,
+        on_stop,
+        on_cancel,
+        on_preset,
+        on_region,
+        on_reset,
+        on_settings,
+        on_export,
+    }: ControlActions,
 ) -> Element {
     let mut state = use_app_state();
     let mode = state.mode.read();
     let duration = *state.duration_ms.read();
+    let paused = *state.paused.read();
+    let (badge_class, pause_title) = if paused {
+        ("timer-badge paused", "Resume")
+    } else {
+        ("timer-badge", "Pause")
+    };
 
     let sec = (duration / 1000) % 60;
     let min = (duration / 1000) / 60;
     let time_str = format!("{min:02}:{sec:02}");
 
+    // Counts down to the automatic stop
+    let left_str = state.max_duration_ms.read().map(|max| {
+        let left = max.saturating_sub(u64::try_from(duration).unwrap_or(0)) / 1000;
+        format!("-{:02}:{:02}", left / 60, left % 60)
+    });
+
+    let countdown_left = state.countdown_left_ms.read().div_ceil(1000);
+    let format = state.export_format.read().clone();
+
     rsx! {
         div {
             class: "control-shell",
 
-            // ZONE 1: Left (Empty for now, cleaner look)
-            if *mode != AppMode::Exporting {
+            // ZONE 1: Left (What to record, countdown length before recording)
+            if *mode == AppMode::Idle {
+                div {
+                    class: "zone-left",
+                    CaptureSetup { on_preset }
+                }
+            } else if *mode != AppMode::Exporting {
                 div { class: "zone-left" }
             }
 
@@ -35,21 +83,68 @@ pub fn ControlBar(
                         onclick: move |_| on_record.call(()),
                         div { class: "record-dot" }
                     }
+                } else if *mode == AppMode::Countdown {
+                    // Counted down in the pill, which is never recorded
+                    div {
+                        class: "timer-badge paused",
+                        span { "Recording in" }
+                        span { class: "countdown", "{countdown_left}" }
+                    }
                 } else if *mode == AppMode::Recording {
                     div {
-                        class: "timer-badge",
+                        class: "{badge_class}",
                         div { class: "pulse-dot" }
                         span { "{time_str}" }
+                        {left_str.map(|left| rsx! {
+                            span { class: "timer-left", title: "Until recording stops", "{left}" }
+                        })}
                     }
                 } else if *mode == AppMode::Review {
                      span { class: "review-text", "Review" }
                 }
             }
 
-            // ZONE 3: Right (Stop/Export)
+            // ZONE 3: Right (Region/Stop/Export)
             div {
                 class: "zone-right",
-                if *mode == AppMode::Recording {
+                if *mode == AppMode::Idle {
+                    RegionFields { on_region }
+                    button {
+                        class: "icon-btn",
+                        title: "Reset to defaults",
+                        onclick: move |_| on_reset.call(()),
+                        IconReset {}
+                    }
+                    button {
+                        class: "icon-btn",
+                        title: "Settings",
+                        onclick: move |_| on_settings.call(()),
+                        "⚙"
+                    }
+                } else if *mode == AppMode::Countdown {
+                    button {
+                        class: "stop-btn",
+                        title: "Cancel (Esc)",
+                        onclick: move |_| on_cancel.call(()),
+                        IconStop {}
+                    }
+                } else if *mode == AppMode::Recording {
+                    button {
+                        class: "icon-btn",
+                        title: pause_title,
+                        onclick: move |_| on_pause.call(()),
+                        if paused {
+                            IconResume {}
+                        } else {
+                            IconPause {}
+                        }
+                    }
+                    button {
+                        class: "icon-btn",
+                        title: "Discard (Esc)",
+                        onclick: move |_| on_cancel.call(()),
+                        IconDiscard {}
+                    }
                     button {
                         class: "stop-btn",
                         onclick: move |_| on_stop.call(()),
@@ -58,7 +153,13 @@ pub fn ControlBar(
                 } else if *mode == AppMode::Review {
                     select {
                         class: "fmt-select",
-                        onchange: move |evt| state.export_format.set(evt.value()),
+                        value: "{format}",
+                        // Each format starts from the settings that suit it
+                        onchange: move |evt| {
+                            Settings::from_state(&state)
+                                .with_format_defaults(&evt.value())
+                                .apply(&mut state);
+                        },
                         option { value: "gif", "GIF" }
                         option { value: "mp4", "MP4" }
                         option { value: "webp", "WebP" }
@@ -0,0 +1,136 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_precision_loss)]
+#![allow(clippy::cast_sign_loss)]
+
+use mandygif_protocol::{EncoderCommand, LoopMode};
+
+/// Rough size of the export `cmd` makes of a `source_width`x`source_height`
+/// recording, to show before encoding.
+///
+/// Based on typical screen content; busy video can come out several
+/// times larger.
+#[must_use]
+pub fn estimated_export_bytes(cmd: &EncoderCommand, source_width: u32, source_height: u32) -> u64 {
+    // Bits per output pixel per frame after compression
+    let (trim, fps, scale_px, bpp) = match cmd {
+        // Ping-pong plays every frame twice
+        EncoderCommand::Gif {
+            trim,
+            fps,
+            scale_px,
+            loop_mode,
+            ..
+        } => {
+            let passes = if *loop_mode == LoopMode::Pingpong {
+                2.0
+            } else {
+                1.0
+            };
+            (trim, fps, scale_px, passes)
+        }
+        EncoderCommand::Mp4 {
+            trim,
+            fps,
+            scale_px,
+            quality,
+            ..
+        } => (trim, fps, scale_px, 0.02 + 0.15 * f64::from(*quality)),
+        EncoderCommand::Webp {
+            trim,
+            fps,
+            scale_px,
+            lossless: true,
+            ..
+        } => (trim, fps, scale_px, 1.5),
+        EncoderCommand::Webp {
+            trim,
+            fps,
+            scale_px,
+            quality,
+            ..
+        } => (trim, fps, scale_px, 0.1 + 0.6 * f64::from(*quality)),
+    };
+    if source_width == 0 {
+        return 0;
+    }
+    let width = f64::from(scale_px.unwrap_or(source_width));
+    let height = width * f64::from(source_height) / f64::from(source_width);
+    let seconds = trim.end_ms.saturating_sub(trim.start_ms) as f64 / 1000.0;
+    (width * height * f64::from(*fps) * seconds * bpp / 8.0) as u64
+}
+
+/// `bytes` in KB or MB, whichever reads better.
+#[must_use]
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1 << 20 {
+        format!("{:.1} MB", bytes as f64 / f64::from(1 << 20))
+    } else {
+        format!("{} KB", (bytes >> 10).max(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mandygif_protocol::{FrameRateMode, TrimRange};
+    use std::path::PathBuf;
+
+    fn trim() -> TrimRange {
+        TrimRange {
+            start_ms: 0,
+            end_ms: 10_000,
+        }
+    }
+
+    #[test]
+    fn test_export_size_estimates() {
+        let webp = |quality, lossless| EncoderCommand::Webp {
+            input: PathBuf::from("/tmp/clip.mp4"),
+            trim: trim(),
+            fps: 20,
+            scale_px: Some(480),
+            quality,
+            lossless,
+            captions: vec![],
+            overlays: vec![],
+            annotations: vec![],
+            camera: vec![],
+            cursor_zoom: None,
+            out: PathBuf::from("/tmp/out.webp"),
+        };
+
+        // 480x270 (scaled from 1920x1080), 200 frames at 0.58 bits per pixel
+        let estimate = |cmd| estimated_export_bytes(&cmd, 1920, 1080);
+        assert_eq!(estimate(webp(0.8, false)), 1_879_200);
+        assert!(estimate(webp(0.2, false)) < 1_879_200);
+        assert!(estimate(webp(0.2, true)) > 1_879_200);
+        assert_eq!(estimated_export_bytes(&webp(0.8, false), 0, 0), 0);
+        assert_eq!(format_size(1_879_200), "1.8 MB");
+        assert_eq!(format_size(100), "1 KB");
+    }
+
+    #[test]
+    fn test_pingpong_gif_doubles_the_estimate() {
+        let gif = |loop_mode| EncoderCommand::Gif {
+            input: PathBuf::from("/tmp/clip.mp4"),
+            trim: trim(),
+            fps: 15,
+            scale_px: Some(480),
+            loop_mode,
+            frame_rate: FrameRateMode::Constant,
+            captions: vec![],
+            overlays: vec![],
+            annotations: vec![],
+            camera: vec![],
+            cursor_zoom: None,
+            out: PathBuf::from("/tmp/out.gif"),
+        };
+
+        let normal = estimated_export_bytes(&gif(LoopMode::Normal), 1920, 1080);
+        assert_eq!(normal, 2_430_000);
+        assert_eq!(
+            estimated_export_bytes(&gif(LoopMode::Pingpong), 1920, 1080),
+            2 * normal
+        );
+    }
+}
@@ -0,0 +1,149 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+
+use super::export_estimate::{estimated_export_bytes, format_size};
+use super::watermark_fields::WatermarkFields;
+use crate::hooks::export::export_job;
+use crate::processes::build_encode_cmd;
+use crate::state::use_app_state;
+use dioxus::prelude::*;
+use mandygif_protocol::LoopMode;
+
+/// Frame rates offered for exports.
+const FPS_CHOICES: [u32; 6] = [10, 15, 20, 24, 30, 60];
+
+/// Export widths offered, in pixels.
+const WIDTH_CHOICES: [u32; 6] = [320, 480, 640, 800, 1280, 1920];
+
+/// `choices` plus `current` if it is not one of them, e.g. from an older
+/// settings file.
+fn with_current(choices: &[u32], current: u32) -> Vec<u32> {
+    let mut all = choices.to_vec();
+    if !all.contains(&current) {
+        all.push(current);
+        all.sort_unstable();
+    }
+    all
+}
+
+/// Export settings for the format picked in the control bar, with a rough
+/// size of the result. Shown in Review mode.
+#[component]
+pub fn ExportPanel() -> Element {
+    let mut state = use_app_state();
+    let format = state.export_format.read().clone();
+    let fps = *state.export_fps.read();
+    let scale = *state.export_scale.read();
+    let quality = (*state.export_quality.read() * 100.0).round() as u32;
+    let lossless = *state.export_lossless.read();
+    let loop_value = match *state.export_loop.read() {
+        LoopMode::Normal => "normal",
+        LoopMode::Pingpong => "pingpong",
+        LoopMode::Once => "once",
+    };
+
+    // Recovered recordings have no known region; the overlay is close
+    let source = state
+        .rec_region
+        .read()
+        .clone()
+        .unwrap_or_else(|| state.region.read().clone());
+    let estimate = export_job(&state).map(|job| {
+        let cmd = build_encode_cmd(job);
+        format_size(estimated_export_bytes(&cmd, source.width, source.height))
+    });
+
+    rsx! {
+        div {
+            class: "panel",
+            label {
+                class: "panel-field",
+                span { "Frame rate" }
+                select {
+                    value: "{fps}",
+                    onchange: move |evt| {
+                        if let Ok(fps) = evt.value().parse() {
+                            state.export_fps.set(fps);
+                        }
+                    },
+                    for choice in with_current(&FPS_CHOICES, fps) {
+                        option { key: "{choice}", value: "{choice}", selected: choice == fps, "{choice} fps" }
+                    }
+                }
+            }
+            label {
+                class: "panel-field",
+                span { "Width" }
+                select {
+                    value: "{scale}",
+                    onchange: move |evt| {
+                        if let Ok(scale) = evt.value().parse() {
+                            state.export_scale.set(scale);
+                        }
+                    },
+                    for choice in with_current(&WIDTH_CHOICES, scale) {
+                        option { key: "{choice}", value: "{choice}", selected: choice == scale, "{choice} px" }
+                    }
+                }
+            }
+            // GIF colours come from a palette, not a quality setting
+            if format != "gif" {
+                label {
+                    class: "panel-field",
+                    span { "Quality" }
+                    input {
+                        r#type: "range",
+                        min: "0",
+                        max: "100",
+                        value: "{quality}",
+                        disabled: format == "webp" && lossless,
+                        oninput: move |evt| {
+                            if let Ok(quality) = evt.value().parse::<f32>() {
+                                state.export_quality.set(quality / 100.0);
+                            }
+                        },
+                    }
+                    span { class: "export-value", "{quality}" }
+                }
+            }
+            if format == "webp" {
+                label {
+                    class: "panel-field",
+                    span { "Lossless" }
+                    input {
+                        r#type: "checkbox",
+                        checked: lossless,
+                        onchange: move |evt| state.export_lossless.set(evt.checked()),
+                    }
+                }
+            }
+            if format == "gif" {
+                label {
+                    class: "panel-field",
+                    span { "Loop" }
+                    select {
+                        value: "{loop_value}",
+                        onchange: move |evt| {
+                            state.export_loop.set(match evt.value().as_str() {
+                                "pingpong" => LoopMode::Pingpong,
+                                "once" => LoopMode::Once,
+                                _ => LoopMode::Normal,
+                            });
+                        },
+                        option { value: "normal", selected: loop_value == "normal", "Forever" }
+                        option { value: "pingpong", selected: loop_value == "pingpong", "Back and forth" }
+                        option { value: "once", selected: loop_value == "once", "Play once" }
+                    }
+                }
+            }
+            WatermarkFields {}
+            {estimate.map(|size| rsx! {
+                div {
+                    class: "export-estimate",
+                    title: "A rough guess; busy footage comes out larger",
+                    "≈ {size}"
+                }
+            })}
+        }
+    }
+}
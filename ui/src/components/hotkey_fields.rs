@@ -0,0 +1,41 @@
+use crate::state::{use_app_state, Hotkeys};
+use dioxus::prelude::*;
+
+/// The shortcuts that can be edited, with their labels.
+const FIELDS: [(&str, fn(&mut Hotkeys) -> &mut String); 4] = [
+    ("Record", |h| &mut h.record),
+    ("Stop", |h| &mut h.stop),
+    ("Pause", |h| &mut h.pause),
+    ("Cancel", |h| &mut h.cancel),
+];
+
+/// Editor for the global shortcuts, listing any that do not work.
+#[component]
+pub fn HotkeyFields() -> Element {
+    let mut state = use_app_state();
+    let mut hotkeys = state.hotkeys.read().clone();
+    let fields = FIELDS.map(|(label, field)| (label, field, field(&mut hotkeys).clone()));
+    let errors = state.hotkey_errors.read().clone();
+
+    rsx! {
+        for (label, field, value) in fields {
+            label {
+                key: "{label}",
+                class: "panel-field",
+                span { "{label}" }
+                input {
+                    class: "panel-text hotkey-input",
+                    r#type: "text",
+                    placeholder: "None",
+                    value: "{value}",
+                    onchange: move |evt| {
+                        *field(&mut state.hotkeys.write()) = evt.value().trim().to_string();
+                    },
+                }
+            }
+        }
+        for error in errors {
+            div { key: "{error}", class: "hotkey-error", "{error}" }
+        }
+    }
+}
@@ -9,6 +9,25 @@ pub fn IconStop() -> Element {
     }
 }
 
+pub fn IconPause() -> Element {
+    rsx! {
+        div {
+            style: "display: flex; gap: 3px;",
+            div { style: "width: 4px; height: 12px; background: currentColor; border-radius: 1px;" }
+            div { style: "width: 4px; height: 12px; background: currentColor; border-radius: 1px;" }
+        }
+    }
+}
+
+pub fn IconResume() -> Element {
+    rsx! {
+        svg {
+            width: "14", height: "14", view_box: "0 0 24 24", fill: "currentColor",
+            polygon { points: "6 4 20 12 6 20 6 4" }
+        }
+    }
+}
+
 pub fn IconExport() -> Element {
     rsx! {
         svg {
@@ -19,3 +38,23 @@ pub fn IconExport() -> Element {
         }
     }
 }
+
+pub fn IconReset() -> Element {
+    rsx! {
+        svg {
+            width: "14", height: "14", view_box: "0 0 24 24", fill: "none", stroke: "currentColor", stroke_width: "2", stroke_linecap: "round", stroke_linejoin: "round",
+            path { d: "M3 12a9 9 0 1 0 3-6.7L3 8" }
+            polyline { points: "3 3 3 8 8 8" }
+        }
+    }
+}
+
+pub fn IconDiscard() -> Element {
+    rsx! {
+        svg {
+            width: "14", height: "14", view_box: "0 0 24 24", fill: "none", stroke: "currentColor", stroke_width: "2", stroke_linecap: "round",
+            line { x1: "6", y1: "6", x2: "18", y2: "18" }
+            line { x1: "18", y1: "6", x2: "6", y2: "18" }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crate::state::use_app_state;
+use dioxus::prelude::*;
+
+const MINUTE_MS: u64 = 60_000;
+const MEGABYTE: u64 = 1 << 20;
+
+/// A limit typed as a whole number of `unit`s; empty or zero is none.
+fn parse_limit(value: &str, unit: u64) -> Option<u64> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(|n| n.saturating_mul(unit))
+}
+
+/// `limit` in whole `unit`s, or empty if there is none.
+fn show_limit(limit: Option<u64>, unit: u64) -> String {
+    limit.map(|l| (l / unit).to_string()).unwrap_or_default()
+}
+
+/// How long and how large a recording may get before it stops by itself.
+#[component]
+pub fn LimitFields() -> Element {
+    let mut state = use_app_state();
+    let minutes = show_limit(*state.max_duration_ms.read(), MINUTE_MS);
+    let megabytes = show_limit(*state.max_bytes.read(), MEGABYTE);
+
+    rsx! {
+        label {
+            class: "panel-field",
+            span { "Stop after" }
+            input {
+                class: "panel-text limit-input",
+                r#type: "number",
+                min: "0",
+                placeholder: "No limit",
+                value: "{minutes}",
+                onchange: move |evt| state.max_duration_ms.set(parse_limit(&evt.value(), MINUTE_MS)),
+            }
+            span { "min" }
+        }
+        label {
+            class: "panel-field",
+            span { "or at" }
+            input {
+                class: "panel-text limit-input",
+                r#type: "number",
+                min: "0",
+                placeholder: "No limit",
+                value: "{megabytes}",
+                onchange: move |evt| state.max_bytes.set(parse_limit(&evt.value(), MEGABYTE)),
+            }
+            span { "MB" }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_read_back_as_typed() {
+        assert_eq!(parse_limit("30", MINUTE_MS), Some(30 * 60 * 1000));
+        assert_eq!(parse_limit(" 4096 ", MEGABYTE), Some(4 << 30));
+        assert_eq!(show_limit(Some(4 << 30), MEGABYTE), "4096");
+
+        // Nothing, zero or nonsense lifts the limit
+        for typed in ["", "0", "-5", "ten"] {
+            assert_eq!(parse_limit(typed, MINUTE_MS), None);
+        }
+        assert_eq!(show_limit(None, MINUTE_MS), "");
+    }
+}
@@ -0,0 +1,45 @@
+use crate::snap::MIN_REGION_SIZE;
+use crate::state::use_app_state;
+use dioxus::prelude::*;
+use mandygif_protocol::CaptureRegion;
+
+/// A typed width or height, made even and at least `MIN_REGION_SIZE`.
+fn field_size(value: i64) -> u32 {
+    u32::try_from(value).unwrap_or(0).max(MIN_REGION_SIZE) & !1
+}
+
+/// The region as editable numbers, in physical pixels; a value takes
+/// effect on Enter or when the field loses focus.
+#[component]
+pub fn RegionFields(on_region: EventHandler<CaptureRegion>) -> Element {
+    let state = use_app_state();
+    let region = state.region.read().clone();
+
+    let field = move |name: &'static str, value: i64, set: fn(&mut CaptureRegion, i64)| {
+        rsx! {
+            label {
+                class: "region-field",
+                span { "{name}" }
+                input {
+                    r#type: "number",
+                    value: "{value}",
+                    // Arrow keys step the number instead of moving the region
+                    onkeydown: move |evt| evt.stop_propagation(),
+                    onchange: move |evt| {
+                        if let Ok(value) = evt.value().parse() {
+                            let mut region = state.region.peek().clone();
+                            set(&mut region, value);
+                            on_region.call(region);
+                        }
+                    },
+                }
+            }
+        }
+    };
+    rsx! {
+        {field("X", region.x.into(), |r, v| r.x = i32::try_from(v).unwrap_or(r.x))}
+        {field("Y", region.y.into(), |r, v| r.y = i32::try_from(v).unwrap_or(r.y))}
+        {field("W", region.width.into(), |r, v| r.width = field_size(v))}
+        {field("H", region.height.into(), |r, v| r.height = field_size(v))}
+    }
+}
@@ -0,0 +1,41 @@
+use super::hotkey_fields::HotkeyFields;
+use super::limit_fields::LimitFields;
+use crate::state::use_app_state;
+use dioxus::prelude::*;
+use mandygif_protocol::FrameRateMode;
+
+/// Longest a variable rate recording goes without a frame.
+const MAX_FRAME_GAP_MS: u32 = 1000;
+
+/// How recordings are made, and the global shortcuts. Shown in Idle mode.
+#[component]
+pub fn SettingsPanel() -> Element {
+    let mut state = use_app_state();
+    let only_changes = matches!(*state.frame_rate.read(), FrameRateMode::Variable { .. });
+
+    rsx! {
+        div {
+            class: "panel",
+            label {
+                class: "panel-field",
+                title: "Skip frames where nothing moved; GIFs hold the last one instead",
+                span { "Record only changes" }
+                input {
+                    r#type: "checkbox",
+                    checked: only_changes,
+                    onchange: move |evt| {
+                        state.frame_rate.set(if evt.checked() {
+                            FrameRateMode::Variable {
+                                max_gap_ms: MAX_FRAME_GAP_MS,
+                            }
+                        } else {
+                            FrameRateMode::Constant
+                        });
+                    },
+                }
+            }
+            LimitFields {}
+            HotkeyFields {}
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::hooks::targets::{monitor_id, use_capture_targets};
+use crate::state::use_app_state;
+use dioxus::prelude::*;
+use mandygif_protocol::{CaptureTarget, MonitorId};
+
+/// `<option>` value for a capture target.
+fn target_value(target: &CaptureTarget) -> String {
+    match target {
+        CaptureTarget::Region => "region".into(),
+        CaptureTarget::Monitor {
+            monitor: MonitorId::Name(name),
+        } => format!("monitor:{name}"),
+        CaptureTarget::Monitor {
+            monitor: MonitorId::Index(index),
+        } => format!("monitor:#{index}"),
+        CaptureTarget::Window { xid } => format!("window:{xid}"),
+    }
+}
+
+fn parse_target(value: &str) -> CaptureTarget {
+    if let Some(name) = value.strip_prefix("monitor:") {
+        let monitor = match name.strip_prefix('#').and_then(|i| i.parse().ok()) {
+            Some(index) => MonitorId::Index(index),
+            None => MonitorId::Name(name.into()),
+        };
+        return CaptureTarget::Monitor { monitor };
+    }
+    match value
+        .strip_prefix("window:")
+        .and_then(|xid| xid.parse().ok())
+    {
+        Some(xid) => CaptureTarget::Window { xid },
+        None => CaptureTarget::Region,
+    }
+}
+
+/// Picker for what to record: the region, a monitor or a window. The
+/// lists are refreshed whenever it gets focus.
+#[component]
+pub fn TargetSelect() -> Element {
+    let mut state = use_app_state();
+    let refresh_targets = use_capture_targets();
+
+    let target = target_value(&state.capture_target.read());
+    let monitors: Vec<_> = state
+        .monitors
+        .read()
+        .iter()
+        .map(|m| {
+            let label = if m.primary {
+                format!("{} (primary)", m.name)
+            } else {
+                m.name.clone()
+            };
+            let value = target_value(&CaptureTarget::Monitor {
+                monitor: monitor_id(m),
+            });
+            (value, label)
+        })
+        .collect();
+    let windows: Vec<_> = state
+        .windows
+        .read()
+        .iter()
+        .filter(|w| !w.title.is_empty())
+        .map(|w| {
+            (
+                target_value(&CaptureTarget::Window { xid: w.xid }),
+                w.title.clone(),
+            )
+        })
+        .collect();
+
+    rsx! {
+        select {
+            class: "target-select",
+            title: "Record",
+            value: "{target}",
+            onfocus: move |_| refresh_targets.call(()),
+            onchange: move |evt| state.capture_target.set(parse_target(&evt.value())),
+            option { value: "region", "Region" }
+            if !monitors.is_empty() {
+                optgroup {
+                    label: "Monitors",
+                    for (value, label) in monitors {
+                        option { key: "{value}", value: "{value}", selected: value == target, "{label}" }
+                    }
+                }
+            }
+            if !windows.is_empty() {
+                optgroup {
+                    label: "Windows",
+                    for (value, title) in windows {
+                        option { key: "{value}", value: "{value}", selected: value == target, "{title}" }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+
+use crate::state::{use_app_state, Watermark, WATERMARK_CORNERS};
+use dioxus::prelude::*;
+use std::path::PathBuf;
+
+/// The watermark image and, once one is picked, where and how strongly it
+/// is drawn.
+#[component]
+pub fn WatermarkFields() -> Element {
+    let mut state = use_app_state();
+    let watermark = state.watermark.read().clone();
+    let watermark_path = watermark
+        .as_ref()
+        .map(|w| w.source.display().to_string())
+        .unwrap_or_default();
+
+    rsx! {
+        label {
+            class: "panel-field",
+            span { "Watermark" }
+            input {
+                class: "panel-text",
+                r#type: "text",
+                placeholder: "PNG or SVG file",
+                value: "{watermark_path}",
+                onchange: move |evt| {
+                    let path = evt.value().trim().to_string();
+                    let picked = (!path.is_empty()).then(|| {
+                        let previous = state.watermark.peek().clone();
+                        match previous {
+                            Some(w) => Watermark {
+                                source: PathBuf::from(&path),
+                                ..w
+                            },
+                            None => Watermark::new(PathBuf::from(&path)),
+                        }
+                    });
+                    state.watermark.set(picked);
+                },
+            }
+        }
+        {watermark.map(|w| {
+            let corner = w.corner();
+            let size = (w.rect.w * 100.0).round() as u32;
+            let opacity = (w.opacity * 100.0).round() as u32;
+            rsx! {
+                label {
+                    class: "panel-field",
+                    span { "Corner" }
+                    select {
+                        value: "{corner}",
+                        onchange: move |evt| {
+                            if let Some(w) = state.watermark.write().as_mut() {
+                                let size = w.rect.w;
+                                w.place(&evt.value(), size);
+                            }
+                        },
+                        for (value, label) in WATERMARK_CORNERS {
+                            option { key: "{value}", value: "{value}", selected: value == corner, "{label}" }
+                        }
+                    }
+                }
+                label {
+                    class: "panel-field",
+                    span { "Size" }
+                    input {
+                        r#type: "range",
+                        min: "5",
+                        max: "50",
+                        value: "{size}",
+                        oninput: move |evt| {
+                            if let (Ok(size), Some(w)) =
+                                (evt.value().parse::<f32>(), state.watermark.write().as_mut())
+                            {
+                                let corner = w.corner();
+                                w.place(corner, size / 100.0);
+                            }
+                        },
+                    }
+                    span { class: "export-value", "{size}%" }
+                }
+                label {
+                    class: "panel-field",
+                    span { "Opacity" }
+                    input {
+                        r#type: "range",
+                        min: "0",
+                        max: "100",
+                        value: "{opacity}",
+                        oninput: move |evt| {
+                            if let (Ok(opacity), Some(w)) =
+                                (evt.value().parse::<f32>(), state.watermark.write().as_mut())
+                            {
+                                w.opacity = opacity / 100.0;
+                            }
+                        },
+                    }
+                    span { class: "export-value", "{opacity}%" }
+                }
+            }
+        })}
+    }
+}
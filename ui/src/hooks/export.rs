@@ -0,0 +1,50 @@
+#![allow(clippy::cast_sign_loss)]
+
+use crate::processes::{run_encoder, ExportJob};
+use crate::state::{use_app_state, AppMode, AppState};
+use dioxus::prelude::*;
+
+/// The export the Review settings describe, if there is a recording.
+///
+/// Reads subscribe the caller, so estimates follow the settings.
+pub fn export_job(state: &AppState) -> Option<ExportJob> {
+    let input = state.rec_path.read().clone()?;
+    let dur = *state.duration_ms.read() as u64;
+    Some(ExportJob {
+        input,
+        format: state.export_format.read().clone(),
+        fps: *state.export_fps.read(),
+        trim: (0, dur),
+        scale: *state.export_scale.read(),
+        quality: *state.export_quality.read(),
+        lossless: *state.export_lossless.read(),
+        loop_mode: state.export_loop.read().clone(),
+        frame_rate: *state.rec_frame_rate.read(),
+        out_dir: state.export_dir.read().clone(),
+        overlays: state
+            .watermark
+            .read()
+            .iter()
+            .map(|w| w.overlay(dur))
+            .collect(),
+    })
+}
+
+/// Export the recording as the Review settings describe.
+pub fn use_export() -> Callback<()> {
+    let mut state = use_app_state();
+    Callback::new(move |()| {
+        let Some(job) = export_job(&state) else {
+            return;
+        };
+
+        state.mode.set(AppMode::Exporting);
+
+        spawn(async move {
+            if let Err(e) = run_encoder(job).await {
+                tracing::error!("Encoder failed: {e}");
+            }
+            state.mode.set(AppMode::Idle);
+        });
+    })
+}
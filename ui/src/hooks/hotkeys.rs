@@ -0,0 +1,81 @@
+use super::recorder::RecorderController;
+use crate::state::use_app_state;
+use dioxus::desktop::{window, ShortcutHandle};
+use dioxus::prelude::*;
+use global_hotkey::hotkey::HotKey;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Register the configured global shortcuts for `recorder`'s actions, again
+/// whenever they are changed.
+///
+/// Shortcuts that do not parse or cannot be grabbed (e.g. taken by another
+/// application, or under Wayland) are skipped and listed in
+/// `hotkey_errors` for the user to fix. Under Wayland the list says why
+/// none can be grabbed.
+pub fn use_hotkeys(recorder: &RecorderController) {
+    let mut state = use_app_state();
+    // Not a signal, so it is still there to clean up when the scope drops
+    let handles = use_hook(|| Rc::new(RefCell::new(Vec::<ShortcutHandle>::new())));
+    let (start, stop, pause, cancel) = (
+        recorder.start,
+        recorder.stop,
+        recorder.toggle_pause,
+        recorder.cancel,
+    );
+
+    let registered = Rc::clone(&handles);
+    use_effect(move || {
+        let hotkeys = state.hotkeys.read().clone();
+        for handle in registered.take() {
+            handle.remove();
+        }
+
+        let bindings = [
+            ("Record", hotkeys.record, start),
+            ("Stop", hotkeys.stop, stop),
+            ("Pause", hotkeys.pause, pause),
+            ("Cancel", hotkeys.cancel, cancel),
+        ];
+        let mut errors = Vec::new();
+        for (name, accelerator, action) in bindings {
+            if accelerator.trim().is_empty() {
+                continue;
+            }
+            let hotkey = match accelerator.parse::<HotKey>() {
+                Ok(hotkey) => hotkey,
+                Err(e) => {
+                    tracing::warn!("Ignoring hotkey {accelerator:?}: {e}");
+                    errors.push(format!("{name}: \"{accelerator}\" is not a shortcut"));
+                    continue;
+                }
+            };
+            match window().create_shortcut(hotkey, move || action.call(())) {
+                Ok(handle) => registered.borrow_mut().push(handle),
+                Err(e) => {
+                    tracing::warn!("Could not register hotkey {accelerator:?}: {e:?}");
+                    errors.push(format!("{name}: \"{accelerator}\" could not be registered"));
+                }
+            }
+        }
+        if !errors.is_empty() && on_wayland() {
+            errors.push(
+                "Global shortcuts need an X11 session; under Wayland use the buttons or Esc".into(),
+            );
+        }
+        state.hotkey_errors.set(errors);
+    });
+
+    use_drop(move || {
+        for handle in handles.take() {
+            handle.remove();
+        }
+    });
+}
+
+/// Whether this is a Wayland session, which lets no application grab keys
+/// meant for other windows.
+fn on_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some_and(|display| !display.is_empty())
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session == "wayland")
+}
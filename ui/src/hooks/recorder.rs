@@ -0,0 +1,161 @@
+#![allow(clippy::cast_possible_truncation)]
+
+mod events;
+
+use super::region::framed_region;
+use super::targets::monitor_id;
+use crate::processes::{recover_recording, run_recorder, RecordingJob};
+use crate::state::{use_app_state, AppMode, AppState};
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
+use events::on_event;
+use mandygif_protocol::{CaptureRegion, CaptureTarget, FrameRateMode, RecorderCommand};
+use tokio::sync::mpsc;
+
+pub struct RecorderController {
+    pub start: Callback<()>,
+    pub toggle_pause: Callback<()>,
+    pub stop: Callback<()>,
+    /// Abort the countdown or the recording, keeping nothing.
+    pub cancel: Callback<()>,
+}
+
+pub fn use_recorder() -> RecorderController {
+    let mut state = use_app_state();
+    let rec_window = use_window();
+
+    // A recording interrupted by a crash reopens for review
+    use_hook(move || {
+        spawn(async move {
+            match recover_recording().await {
+                Ok(Some((path, duration_ms))) if *state.mode.read() == AppMode::Idle => {
+                    state.duration_ms.set(duration_ms as i32);
+                    state.rec_path.set(Some(path));
+                    state.rec_frame_rate.set(FrameRateMode::Constant);
+                    state.mode.set(AppMode::Review);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Recovery failed: {e}"),
+            }
+        });
+    });
+
+    let start = Callback::new(move |()| {
+        if !matches!(*state.mode.read(), AppMode::Idle | AppMode::Review) {
+            return;
+        }
+
+        let region = framed_region(&rec_window);
+        let target = state.capture_target.read().clone();
+        state
+            .rec_region
+            .set(recorded_area(&state, &target, &region));
+        // The overlay would show up in a monitor or over a window; it hides
+        // while recording and hotkeys stop it
+        let hide_overlay = target != CaptureTarget::Region;
+        let overlay = rec_window.clone();
+
+        // The region above is what gets recorded, however long the countdown
+        let job = RecordingJob {
+            region,
+            target,
+            delay_ms: *state.countdown_secs.read() * 1000,
+            max_duration_ms: *state.max_duration_ms.read(),
+            max_bytes: *state.max_bytes.read(),
+            frame_rate: *state.frame_rate.read(),
+        };
+        state.rec_frame_rate.set(job.frame_rate);
+        let delay_ms = job.delay_ms;
+        state.mode.set(if delay_ms > 0 {
+            AppMode::Countdown
+        } else {
+            AppMode::Recording
+        });
+        state.countdown_left_ms.set(delay_ms);
+        state.duration_ms.set(0);
+        state.paused.set(false);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (rec_tx, mut rec_rx) = mpsc::unbounded_channel();
+        state.rec_tx.set(Some(rec_tx));
+
+        spawn(async move {
+            if let Err(e) = run_recorder(tx, &mut rec_rx, job).await {
+                tracing::error!("Recorder failed: {e}");
+            }
+        });
+
+        spawn(async move {
+            while let Some(event) = rx.recv().await {
+                on_event(&mut state, event, hide_overlay.then_some(&overlay));
+            }
+
+            if hide_overlay {
+                overlay.set_minimized(false);
+            }
+            // The recorder is gone; unless it stopped cleanly there is nothing to review
+            if matches!(*state.mode.read(), AppMode::Countdown | AppMode::Recording) {
+                state.mode.set(AppMode::Idle);
+                state.rec_tx.set(None);
+                state.paused.set(false);
+            }
+        });
+    });
+
+    let toggle_pause = Callback::new(move |()| {
+        let cmd = if *state.paused.read() {
+            RecorderCommand::Resume
+        } else {
+            RecorderCommand::Pause
+        };
+        if let Some(tx) = state.rec_tx.read().as_ref() {
+            let _ = tx.send(cmd);
+        }
+    });
+
+    let stop = Callback::new(move |()| {
+        if let Some(tx) = state.rec_tx.take() {
+            let _ = tx.send(RecorderCommand::Stop);
+        }
+    });
+
+    let cancel = Callback::new(move |()| {
+        if !matches!(*state.mode.read(), AppMode::Countdown | AppMode::Recording) {
+            return;
+        }
+        // The recorder deletes what it wrote and answers with `Cancelled`
+        if let Some(tx) = state.rec_tx.take() {
+            let _ = tx.send(RecorderCommand::Cancel);
+        }
+    });
+
+    RecorderController {
+        start,
+        toggle_pause,
+        stop,
+        cancel,
+    }
+}
+
+/// Size of what `target` records, for export estimates, when known.
+fn recorded_area(
+    state: &AppState,
+    target: &CaptureTarget,
+    region: &CaptureRegion,
+) -> Option<CaptureRegion> {
+    match target {
+        CaptureTarget::Region => Some(region.clone()),
+        CaptureTarget::Monitor { monitor } => state
+            .monitors
+            .read()
+            .iter()
+            .find(|m| monitor_id(m) == *monitor)
+            .map(|m| m.region.clone()),
+        CaptureTarget::Window { xid } => state
+            .windows
+            .read()
+            .iter()
+            .find(|w| w.xid == *xid)
+            .map(|w| w.region.clone()),
+    }
+}
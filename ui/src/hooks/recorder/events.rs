@@ -0,0 +1,86 @@
+//! The app's side of the recorder's event stream.
+
+#![allow(clippy::cast_possible_truncation)]
+
+use crate::state::{AppMode, AppState};
+use dioxus::desktop::DesktopContext;
+use dioxus::prelude::*;
+use mandygif_protocol::{RecorderEvent, StopReason};
+
+/// Warn when the disk fills up sooner than this into a recording.
+const LOW_DISK_SECS: u64 = 300;
+
+/// Follow the running recorder; `hidden` is the overlay if it is to get
+/// out of the way once capture starts.
+pub(super) fn on_event(
+    state: &mut AppState,
+    event: RecorderEvent,
+    hidden: Option<&DesktopContext>,
+) {
+    match event {
+        RecorderEvent::Countdown { remaining_ms } => {
+            state.countdown_left_ms.set(remaining_ms);
+        }
+        RecorderEvent::Cancelled => {
+            state.mode.set(AppMode::Idle);
+            state.rec_tx.set(None);
+            state.paused.set(false);
+            state.duration_ms.set(0);
+        }
+        RecorderEvent::Progress { pts_ms, .. } => {
+            state.duration_ms.set(pts_ms as i32);
+        }
+        RecorderEvent::Paused { pts_ms } | RecorderEvent::Resumed { pts_ms } => {
+            state
+                .paused
+                .set(matches!(event, RecorderEvent::Paused { .. }));
+            state.duration_ms.set(pts_ms as i32);
+        }
+        RecorderEvent::Stopped {
+            duration_ms,
+            path,
+            dropped_frames,
+            actual_fps,
+            reason,
+        } => {
+            if dropped_frames > 0 {
+                tracing::warn!("Recording dropped {dropped_frames} frames ({actual_fps:.1} fps)");
+            }
+            log_stop_reason(reason);
+            state.mode.set(AppMode::Review);
+            state.duration_ms.set(duration_ms as i32);
+            state.rec_path.set(Some(path));
+            state.rec_tx.set(None);
+            state.paused.set(false);
+        }
+        RecorderEvent::Error { hint, .. } => {
+            tracing::error!("Recorder Error: {hint}");
+        }
+        RecorderEvent::Started {
+            bytes_per_sec,
+            free_bytes,
+            ..
+        } => {
+            state.mode.set(AppMode::Recording);
+            state.countdown_left_ms.set(0);
+            if let Some(overlay) = hidden {
+                overlay.set_minimized(true);
+            }
+            let secs_left = free_bytes.and_then(|free| free.checked_div(bytes_per_sec));
+            if let Some(secs) = secs_left.filter(|&secs| secs < LOW_DISK_SECS) {
+                tracing::warn!("Disk space for only about {secs} s of recording");
+            }
+        }
+        RecorderEvent::Recovered { .. } | RecorderEvent::Targets { .. } => {}
+    }
+}
+
+fn log_stop_reason(reason: StopReason) {
+    match reason {
+        StopReason::User => {}
+        StopReason::TimeLimit => tracing::info!("Recording reached its time limit"),
+        StopReason::SizeLimit => tracing::warn!("Recording reached its size limit"),
+        StopReason::DiskFull => tracing::warn!("Disk full, recording stopped"),
+        StopReason::Error => tracing::error!("Capture failed, keeping what was recorded"),
+    }
+}
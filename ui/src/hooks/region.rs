@@ -0,0 +1,177 @@
+#![allow(clippy::cast_possible_truncation)]
+#![allow(clippy::cast_sign_loss)]
+
+use crate::chrome::OverlayChrome;
+use crate::preset::RegionPreset;
+use crate::snap::snap_to_edges;
+use crate::state::{use_app_state, AppMode, AppState, OVERLAY_TITLE};
+use dioxus::desktop::tao::dpi::{PhysicalPosition, PhysicalSize};
+use dioxus::desktop::tao::event::{Event, WindowEvent};
+use dioxus::desktop::tao::window::Window;
+use dioxus::desktop::{use_window, use_wry_event_handler};
+use dioxus::prelude::*;
+use mandygif_capture::even_size;
+use mandygif_protocol::CaptureRegion;
+use std::time::Duration;
+
+/// How long the overlay has to stay put before it is snapped.
+const SETTLE_MS: u64 = 150;
+
+/// Edges closer than this, in CSS pixels, pull the region onto them.
+const SNAP_DISTANCE: f64 = 12.0;
+
+/// Ways to set the region other than dragging the overlay.
+pub struct RegionController {
+    /// Frame exactly this region (physical pixels), without snapping it.
+    pub place: Callback<CaptureRegion>,
+    /// Pick a shape preset and reshape the region to it right away.
+    pub pick_preset: Callback<RegionPreset>,
+}
+
+/// The screen area inside the overlay's chrome, in physical pixels.
+///
+/// The chrome is laid out in CSS pixels, so it is scaled by the window's
+/// scale factor; the result is clipped to the monitors.
+pub fn framed_region(window: &Window) -> CaptureRegion {
+    let pos = window
+        .inner_position()
+        .unwrap_or(PhysicalPosition::new(0, 0));
+    let size = window.inner_size();
+    let monitors: Vec<CaptureRegion> = monitor_regions(window).collect();
+
+    OverlayChrome::OVERLAY
+        .scaled(window.scale_factor())
+        .capture_region(
+            &CaptureRegion {
+                x: pos.x,
+                y: pos.y,
+                width: size.width,
+                height: size.height,
+            },
+            &monitors,
+        )
+}
+
+/// Where each monitor sits on the virtual desktop, in physical pixels.
+pub fn monitor_regions(window: &Window) -> impl Iterator<Item = CaptureRegion> {
+    window.available_monitors().map(|monitor| {
+        let (pos, size) = (monitor.position(), monitor.size());
+        CaptureRegion {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+        }
+    })
+}
+
+/// Move and size the overlay so that it frames `region` (physical pixels).
+pub fn place_overlay(window: &Window, region: &CaptureRegion) {
+    let target = OverlayChrome::OVERLAY
+        .scaled(window.scale_factor())
+        .window_around(region);
+    let pos = window
+        .inner_position()
+        .unwrap_or(PhysicalPosition::new(0, 0));
+    let size = window.inner_size();
+    // Every change comes back as a move or resize; only ask for real ones
+    if (pos.x, pos.y) != (target.x, target.y) {
+        window.set_outer_position(PhysicalPosition::new(target.x, target.y));
+    }
+    if (size.width, size.height) != (target.width, target.height) {
+        window.set_inner_size(PhysicalSize::new(target.width, target.height));
+    }
+}
+
+/// Track the region in `state.region`, hold it to `state.region_preset`
+/// and pull it onto nearby window and monitor edges once the overlay stops
+/// moving or resizing.
+///
+/// The region being resized is also published for the size readout.
+pub fn use_region() -> RegionController {
+    let mut state = use_app_state();
+    let window = use_window();
+    let mut generation = use_signal(|| 0_u64);
+    // Where the region was last put; it is left alone while it stays there
+    let mut settled = use_signal(|| None::<CaptureRegion>);
+
+    let overlay = window.clone();
+    use_wry_event_handler(move |event, _| {
+        let Event::WindowEvent {
+            event, window_id, ..
+        } = event
+        else {
+            return;
+        };
+        let resizing = match event {
+            WindowEvent::Resized(_) => true,
+            WindowEvent::Moved(_) => false,
+            _ => return,
+        };
+        if *window_id != overlay.id() {
+            return;
+        }
+        state.region.set(framed_region(&overlay));
+        if *state.mode.peek() != AppMode::Idle {
+            return;
+        }
+        if resizing {
+            state.resize_readout.set(Some(framed_region(&overlay)));
+        }
+
+        *generation.write() += 1;
+        let ticket = *generation.peek();
+        let overlay = overlay.clone();
+        spawn(async move {
+            tokio::time::sleep(Duration::from_millis(SETTLE_MS)).await;
+            if *generation.peek() != ticket {
+                return;
+            }
+            state.resize_readout.set(None);
+
+            let region = framed_region(&overlay);
+            if settled.peek().as_ref() == Some(&region) {
+                return;
+            }
+            let previous = settled.peek().clone().unwrap_or_else(|| region.clone());
+            let threshold = (SNAP_DISTANCE * overlay.scale_factor()) as u32;
+            let snapped = snap_to_edges(&region, &snap_edges(&state), threshold, resizing);
+            let shaped = if resizing {
+                state.region_preset.peek().fit(&snapped, &previous)
+            } else {
+                snapped
+            };
+            place_overlay(&overlay, &shaped);
+            settled.set(Some(even_size(&shaped)));
+        });
+    });
+
+    let place_window = window.clone();
+    let place = use_callback(move |region: CaptureRegion| {
+        place_overlay(&place_window, &region);
+        settled.set(Some(even_size(&region)));
+    });
+
+    let pick_preset = use_callback(move |preset: RegionPreset| {
+        state.region_preset.set(preset);
+        place.call(preset.apply(&framed_region(&window)));
+    });
+
+    RegionController { place, pick_preset }
+}
+
+/// Edges of the monitors and of every window except the overlay itself.
+fn snap_edges(state: &AppState) -> Vec<CaptureRegion> {
+    let monitors = state.monitors.peek();
+    let windows = state.windows.peek();
+    monitors
+        .iter()
+        .map(|m| m.region.clone())
+        .chain(
+            windows
+                .iter()
+                .filter(|w| w.title != OVERLAY_TITLE)
+                .map(|w| w.region.clone()),
+        )
+        .collect()
+}
@@ -0,0 +1,62 @@
+use super::region::{monitor_regions, RegionController};
+use crate::preset::RegionPreset;
+use crate::settings::Settings;
+use crate::state::{use_app_state, AppMode, DEFAULT_OVERLAY_SIZE};
+use dioxus::desktop::tao::dpi::LogicalSize;
+use dioxus::desktop::use_window;
+use dioxus::prelude::*;
+use mandygif_capture::on_monitors;
+use mandygif_protocol::CaptureRegion;
+use std::time::Duration;
+
+/// Settings are saved once they have stopped changing for this long.
+const SAVE_DELAY_MS: u64 = 500;
+
+/// Put the overlay back where it was last session, save the settings a
+/// moment after they change, and return a callback that resets them to
+/// the defaults.
+pub fn use_settings(region: &RegionController) -> Callback<()> {
+    let mut state = use_app_state();
+    let window = use_window();
+    let place = region.place;
+    let mut generation = use_signal(|| 0_u64);
+
+    // A saved region on a monitor that has since gone is not restored
+    let startup = window.clone();
+    use_hook(move || {
+        let saved = state.region.peek().clone();
+        let monitors: Vec<CaptureRegion> = monitor_regions(&startup).collect();
+        let on_screen = on_monitors(&saved, &monitors).width > 0;
+        if on_screen {
+            place.call(saved);
+        } else {
+            let (width, height) = DEFAULT_OVERLAY_SIZE;
+            startup.set_inner_size(LogicalSize::new(width, height));
+        }
+    });
+
+    use_effect(move || {
+        let settings = Settings::from_state(&state);
+        *generation.write() += 1;
+        let ticket = *generation.peek();
+        spawn(async move {
+            tokio::time::sleep(Duration::from_millis(SAVE_DELAY_MS)).await;
+            if *generation.peek() != ticket {
+                return;
+            }
+            if let Err(e) = settings.save() {
+                tracing::warn!("Settings not saved: {e:#}");
+            }
+        });
+    });
+
+    use_callback(move |()| {
+        if *state.mode.peek() != AppMode::Idle {
+            return;
+        }
+        Settings::default().apply(&mut state);
+        state.region_preset.set(RegionPreset::Free);
+        let (width, height) = DEFAULT_OVERLAY_SIZE;
+        window.set_inner_size(LogicalSize::new(width, height));
+    })
+}
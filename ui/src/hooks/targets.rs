@@ -0,0 +1,41 @@
+use crate::processes::list_targets;
+use crate::state::use_app_state;
+use dioxus::prelude::*;
+use mandygif_protocol::{CaptureTarget, MonitorId, MonitorInfo};
+
+/// Load the monitors and windows that can be recorded, returning a
+/// callback that lists them again (windows come and go).
+///
+/// A target that is no longer listed falls back to the overlay's region.
+pub fn use_capture_targets() -> Callback<()> {
+    let mut state = use_app_state();
+    let refresh = use_callback(move |()| {
+        spawn(async move {
+            match list_targets().await {
+                Ok((monitors, windows)) => {
+                    let still_there = match &*state.capture_target.peek() {
+                        CaptureTarget::Region => true,
+                        CaptureTarget::Monitor { monitor } => {
+                            monitors.iter().any(|m| monitor_id(m) == *monitor)
+                        }
+                        CaptureTarget::Window { xid } => windows.iter().any(|w| w.xid == *xid),
+                    };
+                    if !still_there {
+                        state.capture_target.set(CaptureTarget::Region);
+                    }
+                    state.monitors.set(monitors);
+                    state.windows.set(windows);
+                }
+                Err(e) => tracing::warn!("Cannot list capture targets: {e}"),
+            }
+        });
+    });
+    use_hook(move || refresh.call(()));
+    refresh
+}
+
+/// How a listed monitor is asked for; names survive monitors being
+/// rearranged.
+pub fn monitor_id(monitor: &MonitorInfo) -> MonitorId {
+    MonitorId::Name(monitor.name.clone())
+}
@@ -3,15 +3,20 @@
 #![allow(non_snake_case)]
 
 mod app;
+mod chrome;
 mod components; // Loads ui/src/components.rs
 mod hooks;
+mod preset;
 mod processes;
+mod settings;
+mod snap;
 mod state;
 
 use app::App;
 use dioxus::desktop::tao::dpi::LogicalSize;
 use dioxus::desktop::{Config, WindowBuilder};
 use dioxus::prelude::*;
+use state::{DEFAULT_OVERLAY_SIZE, OVERLAY_TITLE};
 
 fn main() {
     dioxus_logger::init(tracing::Level::INFO).expect("failed to init logger");
@@ -19,13 +24,16 @@ fn main() {
     let cfg = Config::new()
         .with_window(
             WindowBuilder::new()
-                .with_title("MandyGIF")
+                .with_title(OVERLAY_TITLE)
                 .with_transparent(true)
                 .with_decorations(false)
                 .with_always_on_top(true)
                 .with_maximized(false)
                 .with_resizable(true)
-                .with_inner_size(LogicalSize::new(800.0, 600.0)),
+                .with_inner_size(LogicalSize::new(
+                    DEFAULT_OVERLAY_SIZE.0,
+                    DEFAULT_OVERLAY_SIZE.1,
+                )),
         )
         .with_background_color((0, 0, 0, 0));
 
@@ -0,0 +1,125 @@
+//! Holding the capture region to a fixed shape as it is resized.
+
+use mandygif_capture::even_size;
+use mandygif_protocol::CaptureRegion;
+
+/// A shape the capture region is held to while resizing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RegionPreset {
+    #[default]
+    Free,
+    /// Width to height ratio, e.g. 16:9.
+    Aspect(u32, u32),
+    /// Exact size in physical pixels; later resizes keep its aspect ratio.
+    Size(u32, u32),
+}
+
+impl RegionPreset {
+    /// Presets offered in the UI, in menu order.
+    pub const ALL: [Self; 10] = [
+        Self::Free,
+        Self::Aspect(16, 9),
+        Self::Aspect(4, 3),
+        Self::Aspect(1, 1),
+        Self::Aspect(9, 16),
+        Self::Size(1920, 1080),
+        Self::Size(1280, 720),
+        Self::Size(1080, 1080),
+        Self::Size(1080, 1920),
+        Self::Size(640, 480),
+    ];
+
+    #[must_use]
+    pub fn label(self) -> String {
+        match self {
+            Self::Free => "Free".into(),
+            Self::Aspect(w, h) => format!("{w}:{h}"),
+            Self::Size(w, h) => format!("{w}×{h}"),
+        }
+    }
+
+    /// What `region` becomes when the preset is picked; the top-left
+    /// corner stays put.
+    #[must_use]
+    pub fn apply(self, region: &CaptureRegion) -> CaptureRegion {
+        match self {
+            Self::Size(width, height) => even_size(&CaptureRegion {
+                width,
+                height,
+                ..region.clone()
+            }),
+            _ => self.fit(region, region),
+        }
+    }
+
+    /// `region`, resized from `previous`, held to the preset's aspect ratio.
+    ///
+    /// Whichever side changed most sets the size, so dragging any edge
+    /// works; the top-left corner stays put.
+    #[must_use]
+    pub fn fit(self, region: &CaptureRegion, previous: &CaptureRegion) -> CaptureRegion {
+        let (Self::Aspect(w, h) | Self::Size(w, h)) = self else {
+            return region.clone();
+        };
+        let height_led =
+            region.height.abs_diff(previous.height) > region.width.abs_diff(previous.width);
+        let (width, height) = if height_led {
+            let height = region.height & !1;
+            (scale(height, w, h), height)
+        } else {
+            let width = region.width & !1;
+            (width, scale(width, h, w))
+        };
+        even_size(&CaptureRegion {
+            width,
+            height,
+            ..region.clone()
+        })
+    }
+}
+
+/// `value * num / den`, rounded to the nearest pixel and then down to even.
+fn scale(value: u32, num: u32, den: u32) -> u32 {
+    let scaled = (u64::from(value) * u64::from(num) + u64::from(den) / 2) / u64::from(den.max(1));
+    u32::try_from(scaled).unwrap_or(u32::MAX) & !1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> CaptureRegion {
+        CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_presets_hold_their_shape() {
+        let region = rect(10, 20, 801, 333);
+        assert_eq!(RegionPreset::Free.apply(&region), region);
+        assert_eq!(
+            RegionPreset::Aspect(16, 9).apply(&region),
+            rect(10, 20, 800, 450)
+        );
+        assert_eq!(
+            RegionPreset::Size(1280, 720).apply(&region),
+            rect(10, 20, 1280, 720)
+        );
+
+        // Dragging the bottom edge of a square grows it from the height
+        let square = RegionPreset::Aspect(1, 1);
+        let before = rect(0, 0, 400, 400);
+        assert_eq!(
+            square.fit(&rect(0, 0, 404, 521), &before),
+            rect(0, 0, 520, 520)
+        );
+        // Portrait; applying again changes nothing
+        let tall = RegionPreset::Aspect(9, 16).apply(&rect(0, 0, 405, 100));
+        assert_eq!(tall, rect(0, 0, 404, 718));
+        assert_eq!(RegionPreset::Aspect(9, 16).fit(&tall, &tall), tall);
+    }
+}
@@ -2,142 +2,132 @@
 #![allow(clippy::match_same_arms)]
 #![allow(clippy::uninlined_format_args)]
 
+mod ask;
+mod export;
+
+pub use ask::{list_targets, recover_recording};
+pub use export::{build_encode_cmd, run_encoder, ExportJob};
+
 use anyhow::{Context, Result};
 use mandygif_protocol::*;
-use mandygif_recorder_linux::Recorder;
 use std::path::PathBuf;
-use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::mpsc;
 use tracing::{error, info};
 
-/// Run recorder directly in this process.
-pub async fn run_recorder(
-    tx: mpsc::UnboundedSender<RecorderEvent>,
-    stop_rx: &mut mpsc::UnboundedReceiver<()>,
-    region: CaptureRegion,
-) -> Result<()> {
-    Recorder::init()?;
-
-    let out_path = PathBuf::from("/tmp/mandygif_recording.mp4");
-
-    // Pass references to match library signature
-    let recorder = Recorder::start(&region, 30, false, &out_path)?;
-
-    let _ = tx.send(RecorderEvent::Started { pts_ms: 0 });
-
-    loop {
-        tokio::select! {
-            _ = stop_rx.recv() => {
-                info!("Stop signal received");
-                break;
-            }
-            // FIX: Explicitly match the unit result from sleep
-            () = tokio::time::sleep(Duration::from_millis(100)) => {
-                let ms = recorder.duration_ms();
-                let _ = tx.send(RecorderEvent::Progress { pts_ms: ms });
-            }
-        }
-    }
-
-    let duration = recorder.stop()?;
-
-    let _ = tx.send(RecorderEvent::Stopped {
-        duration_ms: duration,
-        path: out_path,
-    });
-
-    Ok(())
+/// Platform recorder binary, shipped next to the UI executable.
+#[cfg(target_os = "windows")]
+const RECORDER_BIN: &str = "recorder-win";
+#[cfg(target_os = "macos")]
+const RECORDER_BIN: &str = "recorder-mac";
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const RECORDER_BIN: &str = "recorder-linux";
+
+/// Where recordings are written before export.
+const RECORDING_PATH: &str = "/tmp/mandygif_recording.mp4";
+
+/// Everything needed to start a recording.
+pub struct RecordingJob {
+    /// Recorded when `target` is `Region`.
+    pub region: CaptureRegion,
+    pub target: CaptureTarget,
+    /// Countdown before capture starts.
+    pub delay_ms: u64,
+    /// The recording stops by itself after this long, or before the file
+    /// grows past `max_bytes`.
+    pub max_duration_ms: Option<u64>,
+    pub max_bytes: Option<u64>,
+    pub frame_rate: FrameRateMode,
 }
 
-pub async fn run_encoder(
-    input: PathBuf,
-    fmt: &str,
-    fps: u32,
-    trim: (u64, u64),
-    scale: u32,
+/// Run `job` in the platform recorder process.
+///
+/// Commands from `ctl_rx` are forwarded and events to `tx` until the
+/// recording stops, fails or is cancelled; a recorder that dies first is
+/// reported as an error.
+pub async fn run_recorder(
+    tx: mpsc::UnboundedSender<RecorderEvent>,
+    ctl_rx: &mut mpsc::UnboundedReceiver<RecorderCommand>,
+    job: RecordingJob,
 ) -> Result<()> {
-    let exe = std::env::current_exe()?;
-    let bin_dir = exe
-        .parent()
-        .context("Failed to determine executable directory")?;
-    let bin = bin_dir.join("encoder");
-
-    let mut child = Command::new(&bin)
+    let mut child = Command::new(sibling_bin(RECORDER_BIN)?)
         .stdin(std::process::Stdio::piped())
         .stdout(std::process::Stdio::piped())
+        .kill_on_drop(true)
         .spawn()
-        .context("Failed to spawn encoder process")?;
-
-    let cmd = build_encode_cmd(input, fmt, fps, trim, scale);
-
-    if let Some(mut stdin) = child.stdin.take() {
-        let json = to_jsonl(&cmd)?;
-        stdin.write_all(json.as_bytes()).await?;
-    }
+        .context("Failed to spawn recorder process")?;
 
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("Failed to open recorder stdin")?;
     let stdout = child
         .stdout
         .take()
-        .context("Failed to open encoder stdout")?;
+        .context("Failed to open recorder stdout")?;
     let mut reader = BufReader::new(stdout).lines();
 
-    while let Ok(Some(line)) = reader.next_line().await {
-        if let Ok(event) = parse_encoder_event(&line) {
-            match event {
-                EncoderEvent::Done { path } => info!("Export done: {:?}", path),
-                EncoderEvent::Error { hint, .. } => error!("Export error: {}", hint),
-                EncoderEvent::Progress { .. } => {}
+    let start = RecorderCommand::Start {
+        region: job.region,
+        target: job.target,
+        fps: 30,
+        cursor: false,
+        backend: CaptureBackendKind::Auto,
+        audio: AudioOptions::default(),
+        quality: RecordingQuality::High,
+        frame_rate: job.frame_rate,
+        delay_ms: job.delay_ms,
+        max_duration_ms: job.max_duration_ms,
+        max_bytes: job.max_bytes,
+        out: PathBuf::from(RECORDING_PATH),
+    };
+    stdin.write_all(to_jsonl(&start)?.as_bytes()).await?;
+
+    // Errors end the session only before it starts or once stopping;
+    // anything else (e.g. a refused pause) leaves the recording running.
+    let (mut started, mut stopping, mut finished) = (false, false, false);
+    while !finished {
+        tokio::select! {
+            line = reader.next_line() => {
+                let Some(line) = line? else { break };
+                match parse_recorder_event(&line) {
+                    Ok(event) => {
+                        started |= matches!(event, RecorderEvent::Started { .. });
+                        finished = match event {
+                            RecorderEvent::Stopped { .. } | RecorderEvent::Cancelled => true,
+                            RecorderEvent::Error { .. } => !started || stopping,
+                            _ => false,
+                        };
+                        let _ = tx.send(event);
+                    }
+                    Err(e) => error!("Unparseable recorder event: {}", e),
+                }
+            }
+            Some(cmd) = ctl_rx.recv() => {
+                info!("Sending {:?} to recorder", cmd);
+                stopping |= matches!(cmd, RecorderCommand::Stop | RecorderCommand::Cancel);
+                stdin.write_all(to_jsonl(&cmd)?.as_bytes()).await?;
             }
         }
     }
+    if !finished {
+        let _ = tx.send(RecorderEvent::Error {
+            kind: ErrorKind::IoError,
+            hint: "Recorder exited unexpectedly".into(),
+        });
+    }
 
+    drop(stdin);
     child.wait().await?;
     Ok(())
 }
 
-fn build_encode_cmd(
-    input: PathBuf,
-    fmt: &str,
-    fps: u32,
-    trim: (u64, u64),
-    scale: u32,
-) -> EncoderCommand {
-    let tr = TrimRange {
-        start_ms: trim.0,
-        end_ms: trim.1,
-    };
-    let out = PathBuf::from(format!("/tmp/export.{}", fmt));
-
-    match fmt {
-        "gif" => EncoderCommand::Gif {
-            input,
-            trim: tr,
-            fps,
-            scale_px: Some(scale),
-            loop_mode: LoopMode::Normal,
-            captions: vec![],
-            out,
-        },
-        "webp" => EncoderCommand::Webp {
-            input,
-            trim: tr,
-            fps,
-            scale_px: Some(scale),
-            quality: 0.8,
-            lossless: false,
-            captions: vec![],
-            out,
-        },
-        _ => EncoderCommand::Mp4 {
-            input,
-            trim: tr,
-            fps,
-            scale_px: Some(scale),
-            quality: 0.8,
-            captions: vec![],
-            out,
-        },
-    }
+/// Path of a helper binary installed alongside the UI.
+fn sibling_bin(name: &str) -> Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let bin_dir = exe
+        .parent()
+        .context("Failed to determine executable directory")?;
+    Ok(bin_dir.join(name))
 }
@@ -0,0 +1,82 @@
+//! One-off questions answered by a fresh recorder process.
+
+use super::{sibling_bin, RECORDER_BIN, RECORDING_PATH};
+use anyhow::{Context, Result};
+use mandygif_protocol::{
+    parse_recorder_event, recording_lock_path, to_jsonl, MonitorInfo, RecorderCommand,
+    RecorderEvent, WindowInfo,
+};
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tracing::{error, info};
+
+/// Finalize a recording left behind when the recorder (or the UI) crashed.
+///
+/// Returns the recovered file and its duration, or `None` if the last
+/// recording ended cleanly.
+pub async fn recover_recording() -> Result<Option<(PathBuf, u64)>> {
+    let path = PathBuf::from(RECORDING_PATH);
+    if !recording_lock_path(&path).exists() {
+        return Ok(None);
+    }
+
+    let mut recovered = None;
+    for event in ask_recorder(&RecorderCommand::Recover { path }).await? {
+        match event {
+            RecorderEvent::Recovered { path, duration_ms } => {
+                info!("Recovered {} ({} ms)", path.display(), duration_ms);
+                recovered = Some((path, duration_ms));
+            }
+            RecorderEvent::Error { hint, .. } => error!("Recovery failed: {}", hint),
+            _ => {}
+        }
+    }
+    Ok(recovered)
+}
+
+/// Monitors and top-level windows the recorder can capture instead of a
+/// region.
+pub async fn list_targets() -> Result<(Vec<MonitorInfo>, Vec<WindowInfo>)> {
+    for event in ask_recorder(&RecorderCommand::ListTargets).await? {
+        match event {
+            RecorderEvent::Targets { monitors, windows } => return Ok((monitors, windows)),
+            RecorderEvent::Error { hint, .. } => anyhow::bail!("Cannot list targets: {}", hint),
+            _ => {}
+        }
+    }
+    anyhow::bail!("Recorder did not list any targets")
+}
+
+/// Send a single command to a fresh recorder process and collect its
+/// answers; the recorder exits once it has replied.
+async fn ask_recorder(cmd: &RecorderCommand) -> Result<Vec<RecorderEvent>> {
+    let mut child = Command::new(sibling_bin(RECORDER_BIN)?)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to spawn recorder process")?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("Failed to open recorder stdin")?;
+    let stdout = child
+        .stdout
+        .take()
+        .context("Failed to open recorder stdout")?;
+
+    stdin.write_all(to_jsonl(cmd)?.as_bytes()).await?;
+    drop(stdin);
+
+    let mut events = Vec::new();
+    let mut reader = BufReader::new(stdout).lines();
+    while let Some(line) = reader.next_line().await? {
+        match parse_recorder_event(&line) {
+            Ok(event) => events.push(event),
+            Err(e) => error!("Unparseable recorder event: {}", e),
+        }
+    }
+    child.wait().await?;
+    Ok(events)
+}
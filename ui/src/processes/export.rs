@@ -0,0 +1,131 @@
+#![allow(clippy::wildcard_imports)]
+#![allow(clippy::uninlined_format_args)]
+
+use super::sibling_bin;
+use anyhow::{Context, Result};
+use mandygif_protocol::*;
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tracing::{error, info};
+
+/// Everything needed to turn a recording into an export.
+pub struct ExportJob {
+    pub input: PathBuf,
+    pub format: String,
+    pub fps: u32,
+    pub trim: (u64, u64),
+    pub scale: u32,
+    /// 0.0 to 1.0; GIF has no quality setting.
+    pub quality: f32,
+    /// WebP only.
+    pub lossless: bool,
+    pub loop_mode: LoopMode,
+    /// How the recording was made; GIFs made from a variable rate one
+    /// merge unchanged frames into longer delays.
+    pub frame_rate: FrameRateMode,
+    pub out_dir: PathBuf,
+    pub overlays: Vec<Overlay>,
+}
+
+pub async fn run_encoder(job: ExportJob) -> Result<()> {
+    let mut child = Command::new(sibling_bin("encoder")?)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .context("Failed to spawn encoder process")?;
+
+    let cmd = build_encode_cmd(job);
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let json = to_jsonl(&cmd)?;
+        stdin.write_all(json.as_bytes()).await?;
+    }
+
+    let stdout = child
+        .stdout
+        .take()
+        .context("Failed to open encoder stdout")?;
+    let mut reader = BufReader::new(stdout).lines();
+
+    while let Ok(Some(line)) = reader.next_line().await {
+        if let Ok(event) = parse_encoder_event(&line) {
+            match event {
+                EncoderEvent::Done { path } => info!("Export done: {:?}", path),
+                EncoderEvent::Error { hint, .. } => error!("Export error: {}", hint),
+                EncoderEvent::Progress { .. } => {}
+            }
+        }
+    }
+
+    child.wait().await?;
+    Ok(())
+}
+
+/// The encoder command carrying out `job`.
+pub fn build_encode_cmd(job: ExportJob) -> EncoderCommand {
+    let ExportJob {
+        input,
+        format,
+        fps,
+        trim,
+        scale,
+        quality,
+        lossless,
+        loop_mode,
+        frame_rate,
+        out_dir,
+        overlays,
+    } = job;
+    let tr = TrimRange {
+        start_ms: trim.0,
+        end_ms: trim.1,
+    };
+    let out = out_dir.join(format!("export.{}", format));
+
+    match format.as_str() {
+        "gif" => EncoderCommand::Gif {
+            input,
+            trim: tr,
+            fps,
+            scale_px: Some(scale),
+            loop_mode,
+            frame_rate,
+            captions: vec![],
+            overlays,
+            annotations: vec![],
+            camera: vec![],
+            cursor_zoom: None,
+            out,
+        },
+        "webp" => EncoderCommand::Webp {
+            input,
+            trim: tr,
+            fps,
+            scale_px: Some(scale),
+            quality,
+            lossless,
+            captions: vec![],
+            overlays,
+            annotations: vec![],
+            camera: vec![],
+            cursor_zoom: None,
+            out,
+        },
+        _ => EncoderCommand::Mp4 {
+            input,
+            trim: tr,
+            fps,
+            scale_px: Some(scale),
+            quality,
+            captions: vec![],
+            subtitles: SubtitleMode::Burn,
+            audio: ExportAudio::Normalize,
+            overlays,
+            annotations: vec![],
+            camera: vec![],
+            cursor_zoom: None,
+            out,
+        },
+    }
+}
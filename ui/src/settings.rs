@@ -0,0 +1,159 @@
+//! Choices that carry over between sessions, kept in a JSON file.
+
+mod app_state;
+mod file;
+
+use crate::state::{Hotkeys, Watermark};
+use mandygif_protocol::{CaptureRegion, FrameRateMode, LoopMode};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// What is remembered; anything missing from the file keeps its default.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Last region framed by the overlay, in physical pixels.
+    pub region: Option<CaptureRegion>,
+    pub export_format: String,
+    pub export_fps: u32,
+    pub export_scale: u32,
+    /// 0.0 to 1.0, for formats that have a quality setting.
+    pub export_quality: f32,
+    /// WebP only.
+    pub lossless: bool,
+    /// GIF only.
+    pub loop_mode: LoopMode,
+    /// Where exports are written.
+    pub export_dir: PathBuf,
+    pub watermark: Option<Watermark>,
+    pub hotkeys: Hotkeys,
+    /// Whether recordings keep every frame or only the ones that change.
+    pub frame_rate: FrameRateMode,
+    /// Recordings stop on their own after this long, if set.
+    pub max_duration_ms: Option<u64>,
+    /// Recordings stop on their own before growing past this, if set.
+    pub max_bytes: Option<u64>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            region: None,
+            export_format: String::new(),
+            export_fps: 0,
+            export_scale: 0,
+            export_quality: 0.0,
+            lossless: false,
+            loop_mode: LoopMode::Normal,
+            export_dir: std::env::temp_dir(),
+            watermark: None,
+            hotkeys: Hotkeys::default(),
+            frame_rate: FrameRateMode::Constant,
+            max_duration_ms: Some(30 * 60 * 1000),
+            max_bytes: Some(4 << 30),
+        }
+        .with_format_defaults("gif")
+    }
+}
+
+impl Settings {
+    /// Switch to `format` with the fps, width and quality that suit it:
+    /// GIFs stay small and choppy, MP4 keeps the detail.
+    #[must_use]
+    pub fn with_format_defaults(self, format: &str) -> Self {
+        let (fps, scale) = match format {
+            "mp4" => (30, 1280),
+            "webp" => (20, 640),
+            _ => (15, 480),
+        };
+        Self {
+            export_format: format.into(),
+            export_fps: fps,
+            export_scale: scale,
+            export_quality: 0.8,
+            lossless: false,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use mandygif_protocol::CaptionRect;
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("mandygif").join("settings.json");
+        let mut settings = Settings {
+            region: Some(CaptureRegion {
+                x: -1920,
+                y: 40,
+                width: 640,
+                height: 480,
+            }),
+            loop_mode: LoopMode::Once,
+            frame_rate: FrameRateMode::Variable { max_gap_ms: 1000 },
+            max_duration_ms: None,
+            max_bytes: Some(500 << 20),
+            export_dir: PathBuf::from("/home/me/Videos"),
+            watermark: Some(Watermark {
+                source: PathBuf::from("logo.png"),
+                rect: CaptionRect {
+                    x: 0.82,
+                    y: 0.82,
+                    w: 0.15,
+                    h: 0.15,
+                },
+                opacity: 0.5,
+            }),
+            ..Settings::default().with_format_defaults("webp")
+        };
+        settings.hotkeys.stop = "alt+F9".into();
+
+        settings.save_to(&path)?;
+        assert_eq!(Settings::load_from(&path), settings);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_file_gives_defaults() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let loaded = Settings::load_from(&dir.path().join("settings.json"));
+        assert_eq!(loaded, Settings::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_file_gives_defaults() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("settings.json");
+        std::fs::write(&path, r#"{"export_format": "gif", "export_fps": "#)?;
+        assert_eq!(Settings::load_from(&path), Settings::default());
+        Ok(())
+    }
+
+    #[test]
+    fn test_older_file_keeps_new_defaults() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("settings.json");
+        // Written before watermarks and hotkeys were saved
+        std::fs::write(
+            &path,
+            r#"{"export_format": "mp4", "export_fps": 24, "export_scale": 1280}"#,
+        )?;
+
+        let loaded = Settings::load_from(&path);
+        assert_eq!(loaded.export_format, "mp4");
+        assert_eq!(loaded.export_fps, 24);
+        assert_eq!(loaded.watermark, None);
+        assert_eq!(loaded.hotkeys, Hotkeys::default());
+        assert_eq!(loaded.loop_mode, LoopMode::Normal);
+        assert_eq!(loaded.frame_rate, FrameRateMode::Constant);
+        assert_eq!(loaded.max_duration_ms, Some(30 * 60 * 1000));
+        assert_eq!(loaded.max_bytes, Some(4 << 30));
+        Ok(())
+    }
+}
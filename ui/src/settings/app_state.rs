@@ -0,0 +1,44 @@
+//! Settings carried to and from the live app state.
+
+use super::Settings;
+use crate::state::AppState;
+use dioxus::prelude::*;
+
+impl Settings {
+    /// The current choices in `state`. Reads subscribe the caller.
+    pub fn from_state(state: &AppState) -> Self {
+        let region = state.region.read().clone();
+        Self {
+            region: (region.width > 0 && region.height > 0).then_some(region),
+            export_format: state.export_format.read().clone(),
+            export_fps: *state.export_fps.read(),
+            export_scale: *state.export_scale.read(),
+            export_quality: *state.export_quality.read(),
+            lossless: *state.export_lossless.read(),
+            loop_mode: state.export_loop.read().clone(),
+            export_dir: state.export_dir.read().clone(),
+            watermark: state.watermark.read().clone(),
+            hotkeys: state.hotkeys.read().clone(),
+            frame_rate: *state.frame_rate.read(),
+            max_duration_ms: *state.max_duration_ms.read(),
+            max_bytes: *state.max_bytes.read(),
+        }
+    }
+
+    /// Make these the choices in `state`; the region is left to the
+    /// overlay, which frames it.
+    pub fn apply(&self, state: &mut AppState) {
+        state.export_format.set(self.export_format.clone());
+        state.export_fps.set(self.export_fps);
+        state.export_scale.set(self.export_scale);
+        state.export_quality.set(self.export_quality);
+        state.export_lossless.set(self.lossless);
+        state.export_loop.set(self.loop_mode.clone());
+        state.export_dir.set(self.export_dir.clone());
+        state.watermark.set(self.watermark.clone());
+        state.hotkeys.set(self.hotkeys.clone());
+        state.frame_rate.set(self.frame_rate);
+        state.max_duration_ms.set(self.max_duration_ms);
+        state.max_bytes.set(self.max_bytes);
+    }
+}
@@ -0,0 +1,54 @@
+//! Where settings are saved and how they are read back.
+
+use super::Settings;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+impl Settings {
+    /// `$XDG_CONFIG_HOME/mandygif/settings.json`, or under `~/.config`.
+    pub fn path() -> Option<PathBuf> {
+        let config = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config.join("mandygif").join("settings.json"))
+    }
+
+    /// The saved settings, or the defaults if there are none or they
+    /// cannot be read.
+    pub fn load() -> Self {
+        Self::path().map_or_else(Self::default, |path| Self::load_from(&path))
+    }
+
+    /// The settings saved at `path`, or the defaults if there are none or
+    /// they cannot be read.
+    pub fn load_from(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Write the settings, creating the config directory if needed.
+    ///
+    /// # Errors
+    /// Returns error if there is no config directory or it cannot be written.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::path().context("No config directory")?)
+    }
+
+    /// Write the settings to `path`, creating its directory if needed.
+    ///
+    /// # Errors
+    /// Returns error if the directory or file cannot be written.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
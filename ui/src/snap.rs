@@ -0,0 +1,125 @@
+//! Pulling the capture region onto nearby edges and nudging it from the
+//! keyboard.
+
+use mandygif_protocol::CaptureRegion;
+
+/// Smallest region keyboard resizing and the size fields allow.
+pub const MIN_REGION_SIZE: u32 = 16;
+
+/// `region` pulled onto any edge of `edges` (other windows, monitors)
+/// within `threshold` pixels.
+///
+/// A moved region is shifted as a whole; a resized one has each edge
+/// snapped on its own.
+#[must_use]
+pub fn snap_to_edges(
+    region: &CaptureRegion,
+    edges: &[CaptureRegion],
+    threshold: u32,
+    resizing: bool,
+) -> CaptureRegion {
+    let xs: Vec<i64> = edges
+        .iter()
+        .flat_map(|e| [i64::from(e.x), i64::from(e.x) + i64::from(e.width)])
+        .collect();
+    let ys: Vec<i64> = edges
+        .iter()
+        .flat_map(|e| [i64::from(e.y), i64::from(e.y) + i64::from(e.height)])
+        .collect();
+    let (left, top) = (i64::from(region.x), i64::from(region.y));
+    let (right, bottom) = (
+        left + i64::from(region.width),
+        top + i64::from(region.height),
+    );
+
+    let (left, right, top, bottom) = if resizing {
+        (
+            left + pull(left, &xs, threshold).unwrap_or(0),
+            right + pull(right, &xs, threshold).unwrap_or(0),
+            top + pull(top, &ys, threshold).unwrap_or(0),
+            bottom + pull(bottom, &ys, threshold).unwrap_or(0),
+        )
+    } else {
+        let dx = closest(pull(left, &xs, threshold), pull(right, &xs, threshold));
+        let dy = closest(pull(top, &ys, threshold), pull(bottom, &ys, threshold));
+        (left + dx, right + dx, top + dy, bottom + dy)
+    };
+
+    CaptureRegion {
+        x: i32::try_from(left).unwrap_or(region.x),
+        y: i32::try_from(top).unwrap_or(region.y),
+        width: u32::try_from(right - left).unwrap_or(region.width),
+        height: u32::try_from(bottom - top).unwrap_or(region.height),
+    }
+}
+
+/// `region` moved by (`dx`, `dy`) and grown by (`dw`, `dh`) pixels, never
+/// smaller than `MIN_REGION_SIZE`.
+#[must_use]
+pub fn adjust(region: &CaptureRegion, dx: i32, dy: i32, dw: i32, dh: i32) -> CaptureRegion {
+    CaptureRegion {
+        x: region.x.saturating_add(dx),
+        y: region.y.saturating_add(dy),
+        width: region.width.saturating_add_signed(dw).max(MIN_REGION_SIZE),
+        height: region.height.saturating_add_signed(dh).max(MIN_REGION_SIZE),
+    }
+}
+
+/// Distance from `at` to the nearest of `lines`, if within `threshold`.
+fn pull(at: i64, lines: &[i64], threshold: u32) -> Option<i64> {
+    lines
+        .iter()
+        .map(|line| line - at)
+        .filter(|d| d.unsigned_abs() <= u64::from(threshold))
+        .min_by_key(|d| d.unsigned_abs())
+}
+
+fn closest(a: Option<i64>, b: Option<i64>) -> i64 {
+    match (a, b) {
+        (Some(a), Some(b)) if b.abs() < a.abs() => b,
+        (Some(a), _) => a,
+        (None, b) => b.unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> CaptureRegion {
+        CaptureRegion {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_snaps_to_nearby_edges() {
+        let edges = [rect(0, 0, 1920, 1080), rect(100, 100, 500, 400)];
+
+        // Moving: the closer of the two edges wins, size is kept
+        let moved = snap_to_edges(&rect(605, 97, 300, 200), &edges, 8, false);
+        assert_eq!(moved, rect(600, 100, 300, 200));
+
+        // Resizing: each edge snaps on its own
+        let resized = snap_to_edges(&rect(105, 300, 1810, 200), &edges, 8, true);
+        assert_eq!(resized, rect(100, 300, 1820, 200));
+
+        // Too far from anything
+        let free = rect(700, 700, 100, 100);
+        assert_eq!(snap_to_edges(&free, &edges, 8, false), free);
+    }
+
+    #[test]
+    fn test_keyboard_adjustments() {
+        let region = rect(0, 0, 100, 50);
+        assert_eq!(adjust(&region, -1, 10, 0, 0), rect(-1, 10, 100, 50));
+        assert_eq!(adjust(&region, 0, 0, 10, -2), rect(0, 0, 110, 48));
+        assert_eq!(
+            adjust(&region, 0, 0, -200, -200),
+            rect(0, 0, MIN_REGION_SIZE, MIN_REGION_SIZE)
+        );
+    }
+}
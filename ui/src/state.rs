@@ -1,10 +1,31 @@
+mod watermark;
+
+pub use watermark::{Watermark, WATERMARK_CORNERS};
+
+use crate::preset::RegionPreset;
+use crate::settings::Settings;
 use dioxus::prelude::*;
+use mandygif_protocol::{
+    CaptureRegion, CaptureTarget, FrameRateMode, LoopMode, MonitorInfo, RecorderCommand, WindowInfo,
+};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tokio::sync::mpsc::UnboundedSender;
 
+/// Title of the overlay window, which snapping must not snap to.
+pub const OVERLAY_TITLE: &str = "MandyGIF";
+
+/// Size the overlay opens at, in CSS pixels, when there is no saved region.
+pub const DEFAULT_OVERLAY_SIZE: (f64, f64) = (800.0, 600.0);
+
+/// Countdown lengths the control bar cycles through, in seconds.
+pub const COUNTDOWN_CHOICES: [u64; 3] = [0, 3, 5];
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AppMode {
     Idle,
+    /// Waiting out the countdown; the region is already fixed.
+    Countdown,
     Recording,
     Review,
     Exporting,
@@ -14,23 +35,105 @@ pub enum AppMode {
 pub struct AppState {
     pub mode: Signal<AppMode>,
     pub duration_ms: Signal<i32>,
+    /// What to record; `Region` means the area inside the overlay.
+    pub capture_target: Signal<CaptureTarget>,
+    /// Choices for `capture_target`, as last listed by the recorder.
+    pub monitors: Signal<Vec<MonitorInfo>>,
+    pub windows: Signal<Vec<WindowInfo>>,
+    /// What the overlay frames, in physical pixels.
+    pub region: Signal<CaptureRegion>,
+    /// Shape the region is held to while resizing.
+    pub region_preset: Signal<RegionPreset>,
+    /// The region being resized, for the live size readout.
+    pub resize_readout: Signal<Option<CaptureRegion>>,
+    /// Seconds counted down before recording, one of `COUNTDOWN_CHOICES`.
+    pub countdown_secs: Signal<u64>,
+    pub countdown_left_ms: Signal<u64>,
+    /// Whether new recordings keep every frame or only the ones that change.
+    pub frame_rate: Signal<FrameRateMode>,
+    /// Recordings stop on their own after this long.
+    pub max_duration_ms: Signal<Option<u64>>,
+    /// Recordings stop on their own before growing past this.
+    pub max_bytes: Signal<Option<u64>>,
     pub rec_path: Signal<Option<PathBuf>>,
-    pub stop_tx: Signal<Option<UnboundedSender<()>>>,
+    /// Screen area of the recording, when known.
+    pub rec_region: Signal<Option<CaptureRegion>>,
+    /// How the recording was made; a recovered one counts as `Constant`.
+    pub rec_frame_rate: Signal<FrameRateMode>,
+    /// Controls for the running recorder process (pause/resume/stop).
+    pub rec_tx: Signal<Option<UnboundedSender<RecorderCommand>>>,
+    pub paused: Signal<bool>,
     pub export_format: Signal<String>,
     pub export_fps: Signal<u32>,
     pub export_scale: Signal<u32>,
+    pub export_quality: Signal<f32>,
+    pub export_lossless: Signal<bool>,
+    pub export_loop: Signal<LoopMode>,
+    pub export_dir: Signal<PathBuf>,
+    /// Logo stamped on exports, if one is picked.
+    pub watermark: Signal<Option<Watermark>>,
+    pub hotkeys: Signal<Hotkeys>,
+    /// Why some of `hotkeys` are not working, one line each.
+    pub hotkey_errors: Signal<Vec<String>>,
+}
+
+/// Global shortcuts, in `global-hotkey` syntax such as `ctrl+shift+R`.
+///
+/// They work while another window has focus, which on Linux needs X11.
+/// An empty one is left unbound.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hotkeys {
+    pub record: String,
+    pub stop: String,
+    pub pause: String,
+    pub cancel: String,
+}
+
+impl Default for Hotkeys {
+    fn default() -> Self {
+        Self {
+            record: "ctrl+shift+R".into(),
+            stop: "ctrl+shift+S".into(),
+            pause: "ctrl+shift+P".into(),
+            cancel: "ctrl+shift+Escape".into(),
+        }
+    }
 }
 
 impl AppState {
+    /// Fresh state with the choices saved last session.
     pub fn new() -> Self {
+        let saved = Settings::load();
         Self {
             mode: Signal::new(AppMode::Idle),
             duration_ms: Signal::new(0),
+            capture_target: Signal::new(CaptureTarget::Region),
+            monitors: Signal::new(Vec::new()),
+            windows: Signal::new(Vec::new()),
+            region: Signal::new(saved.region.unwrap_or_default()),
+            region_preset: Signal::new(RegionPreset::Free),
+            resize_readout: Signal::new(None),
+            countdown_secs: Signal::new(3),
+            countdown_left_ms: Signal::new(0),
+            frame_rate: Signal::new(saved.frame_rate),
+            max_duration_ms: Signal::new(saved.max_duration_ms),
+            max_bytes: Signal::new(saved.max_bytes),
             rec_path: Signal::new(None),
-            stop_tx: Signal::new(None),
-            export_format: Signal::new("gif".to_string()),
-            export_fps: Signal::new(15),
-            export_scale: Signal::new(480),
+            rec_region: Signal::new(None),
+            rec_frame_rate: Signal::new(FrameRateMode::Constant),
+            rec_tx: Signal::new(None),
+            paused: Signal::new(false),
+            export_format: Signal::new(saved.export_format),
+            export_fps: Signal::new(saved.export_fps),
+            export_scale: Signal::new(saved.export_scale),
+            export_quality: Signal::new(saved.export_quality),
+            export_lossless: Signal::new(saved.lossless),
+            export_loop: Signal::new(saved.loop_mode),
+            export_dir: Signal::new(saved.export_dir),
+            watermark: Signal::new(saved.watermark),
+            hotkeys: Signal::new(saved.hotkeys),
+            hotkey_errors: Signal::new(Vec::new()),
         }
     }
 }
@@ -0,0 +1,89 @@
+//! The logo stamped on every export.
+
+use mandygif_protocol::{CaptionRect, Overlay};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Corners a watermark can sit in, with their menu labels.
+pub const WATERMARK_CORNERS: [(&str, &str); 4] = [
+    ("top-left", "Top left"),
+    ("top-right", "Top right"),
+    ("bottom-left", "Bottom left"),
+    ("bottom-right", "Bottom right"),
+];
+
+/// Gap between a watermark and the frame edge, as a fraction of the frame.
+const WATERMARK_MARGIN: f32 = 0.03;
+
+/// Logo stamped on every export, whatever the format.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub source: PathBuf,
+    /// Box the image is fitted into, as fractions of the frame.
+    pub rect: CaptionRect,
+    pub opacity: f32,
+}
+
+impl Watermark {
+    /// `source` in the bottom-right corner, 15% of the frame wide.
+    pub fn new(source: PathBuf) -> Self {
+        let mut watermark = Self {
+            source,
+            rect: CaptionRect {
+                x: 0.0,
+                y: 0.0,
+                w: 0.15,
+                h: 0.15,
+            },
+            opacity: 0.7,
+        };
+        watermark.place("bottom-right", 0.15);
+        watermark
+    }
+
+    /// Which of `WATERMARK_CORNERS` the box is nearest.
+    pub fn corner(&self) -> &'static str {
+        let centre = |at: f32, size: f32| at + size / 2.0 < 0.5;
+        match (
+            centre(self.rect.x, self.rect.w),
+            centre(self.rect.y, self.rect.h),
+        ) {
+            (true, true) => "top-left",
+            (false, true) => "top-right",
+            (true, false) => "bottom-left",
+            (false, false) => "bottom-right",
+        }
+    }
+
+    /// Move the box into `corner`, `size` of the frame across.
+    pub fn place(&mut self, corner: &str, size: f32) {
+        let size = size.clamp(0.05, 0.5);
+        let far = 1.0 - WATERMARK_MARGIN - size;
+        self.rect = CaptionRect {
+            x: if corner.ends_with("left") {
+                WATERMARK_MARGIN
+            } else {
+                far
+            },
+            y: if corner.starts_with("top") {
+                WATERMARK_MARGIN
+            } else {
+                far
+            },
+            w: size,
+            h: size,
+        };
+    }
+
+    /// Overlay covering the whole exported clip.
+    pub fn overlay(&self, duration_ms: u64) -> Overlay {
+        Overlay {
+            source: self.source.clone(),
+            rect: self.rect.clone(),
+            opacity: self.opacity,
+            start_ms: 0,
+            end_ms: duration_ms,
+            fade_ms: 0,
+        }
+    }
+}